@@ -0,0 +1,232 @@
+//! # activity.rs
+//! Calls the Withings API to get the daily activity summaries and the intraday activity
+//! https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getactivity
+//! https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getintradayactivity
+
+use crate::api::client::WithingsClient;
+use crate::api::measure::MEASURE_V2_PATH;
+use crate::api::pagination::{next_offset, Items, Pages};
+use crate::api::params::{
+    split_windows, DataField, DateRange, DayRangeParams, DayRangeParamsBuilder,
+};
+use crate::models::activity::{
+    Activity, ActivityField, IntradayField, IntradaySample, ResponseActivity, ResponseIntraday,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+/// Longest window, in seconds, Withings accepts for one intraday activity request
+pub const INTRADAY_MAX_WINDOW: i64 = 24 * 60 * 60;
+
+/// Represents the parameters for a daily activity request.
+///
+/// Either a range of days or `lastupdate` must be set.
+///
+/// # Examples
+///
+/// ```
+/// use withings_rs::api::activity::ActivityParams;
+/// use withings_rs::models::{activity::ActivityField, date::Date};
+///
+/// let params = ActivityParams::builder()
+///     .startdate(Date::new(2024, 1, 1).unwrap())
+///     .enddate(Date::new(2024, 1, 31).unwrap())
+///     .data_fields([ActivityField::Steps, ActivityField::Distance])
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.to_query_params()["startdateymd"], "2024-01-01");
+/// assert_eq!(params.to_query_params()["data_fields"], "steps,distance");
+/// ```
+pub type ActivityParams = DayRangeParams<ActivityField>;
+
+/// Builder for `ActivityParams`.
+pub type ActivityParamsBuilder = DayRangeParamsBuilder<ActivityField>;
+
+impl DataField for ActivityField {
+    const ACTION: &'static str = "getactivity";
+
+    fn name(&self) -> &'static str {
+        ActivityField::name(self)
+    }
+}
+
+/// Retrieves one page of daily activity summaries from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `params` - The `ActivityParams` struct containing the parameters for the API call.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::activity::ResponseActivity` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getactivity
+pub fn get_activity_page(
+    client: &WithingsClient,
+    params: &ActivityParams,
+) -> Result<ResponseActivity, Box<dyn Error>> {
+    client.get(MEASURE_V2_PATH, &params.to_query_params())
+}
+
+/// Returns an iterator over the pages of a daily activity request, following the `offset`
+/// returned by each response.
+pub fn get_activity_pages<'a>(
+    client: &'a WithingsClient,
+    params: &ActivityParams,
+) -> Pages<'a, ResponseActivity> {
+    let mut params = params.clone();
+    Pages::new(move |offset| {
+        if let Some(offset) = offset {
+            params = params.with_offset(offset);
+        }
+        let response = get_activity_page(client, &params)?;
+        let next = next_offset(response.body.more, response.body.offset);
+        Ok((response, next))
+    })
+}
+
+/// Returns an iterator over every daily activity summary of a request, across all pages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{activity, WithingsClient};
+/// use withings_rs::models::date::Date;
+///
+/// let client = WithingsClient::default();
+/// let params = activity::ActivityParams::builder()
+///     .startdate(Date::new(2024, 1, 1).unwrap())
+///     .enddate(Date::new(2024, 1, 31).unwrap())
+///     .build()
+///     .unwrap();
+/// for day in activity::get_activities(&client, &params) {
+///     let day = day.unwrap();
+///     println!("{}: {:?} steps", day.date, day.steps);
+/// }
+/// ```
+pub fn get_activities<'a>(
+    client: &'a WithingsClient,
+    params: &ActivityParams,
+) -> Items<'a, ResponseActivity, Activity> {
+    get_activity_pages(client, params).items(|response| response.body.activities)
+}
+
+/// Represents the parameters for an intraday activity request over a range of time.
+///
+/// The range may be longer than the 24 hours Withings accepts per request,
+/// `get_intraday_series` splits it into valid windows.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use withings_rs::api::{activity::IntradayParams, params::DateRange};
+///
+/// let start = UNIX_EPOCH + Duration::from_secs(1_704_067_200);
+/// let range = DateRange::new(start, start + Duration::from_secs(3 * 24 * 60 * 60)).unwrap();
+/// let params = IntradayParams::new(range);
+/// assert_eq!(params.windows().len(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IntradayParams {
+    start: i64,
+    end: i64,
+    data_fields: Vec<IntradayField>,
+}
+
+impl IntradayParams {
+    /// Creates the parameters for a range of time, requesting every field.
+    pub fn new(range: DateRange) -> Self {
+        let (start, end) = range.to_epoch_seconds();
+        IntradayParams {
+            start,
+            end,
+            data_fields: Vec::new(),
+        }
+    }
+
+    /// Selects the fields of the samples to return. Every field is returned by default.
+    pub fn data_fields(mut self, fields: impl IntoIterator<Item = IntradayField>) -> Self {
+        self.data_fields.extend(fields);
+        self
+    }
+
+    /// Splits the range into consecutive windows Withings accepts, as inclusive epoch seconds.
+    pub fn windows(&self) -> Vec<(i64, i64)> {
+        split_windows(self.start, self.end, INTRADAY_MAX_WINDOW)
+    }
+
+    /// Converts the parameters of one window into a `HashMap` of request parameters.
+    pub fn to_query_params(&self, (start, end): (i64, i64)) -> HashMap<&str, String> {
+        let mut params = HashMap::new();
+
+        params.insert("action", "getintradayactivity".to_string());
+        params.insert("startdate", start.to_string());
+        params.insert("enddate", end.to_string());
+        if !self.data_fields.is_empty() {
+            let fields: Vec<&str> = self.data_fields.iter().map(|f| f.name()).collect();
+            params.insert("data_fields", fields.join(","));
+        }
+
+        params
+    }
+}
+
+/// Retrieves the intraday activity of one window from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `params` - The fields to request.
+/// * `window` - The window to request, as inclusive epoch seconds. Withings only returns the
+///   first 24 hours of longer windows.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::activity::ResponseIntraday` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getintradayactivity
+pub fn get_intraday_activity(
+    client: &WithingsClient,
+    params: &IntradayParams,
+    window: (i64, i64),
+) -> Result<ResponseIntraday, Box<dyn Error>> {
+    client.get(MEASURE_V2_PATH, &params.to_query_params(window))
+}
+
+/// Retrieves the intraday activity of the whole range of `params`, one window at a time in
+/// chronological order, merged into a single series keyed by epoch seconds.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::{Duration, SystemTime};
+/// use withings_rs::api::{activity, params::DateRange, WithingsClient};
+/// use withings_rs::models::activity::IntradayField;
+///
+/// let client = WithingsClient::default();
+/// let now = SystemTime::now();
+/// let range = DateRange::new(now - Duration::from_secs(7 * 24 * 60 * 60), now).unwrap();
+/// let params = activity::IntradayParams::new(range)
+///     .data_fields([IntradayField::Steps, IntradayField::HeartRate]);
+/// for (timestamp, sample) in activity::get_intraday_series(&client, &params).unwrap() {
+///     println!("{}: {:?} bpm", timestamp, sample.heart_rate);
+/// }
+/// ```
+///
+/// # Errors
+/// - Returns an error if a request fails. Nothing is returned for the windows fetched before.
+pub fn get_intraday_series(
+    client: &WithingsClient,
+    params: &IntradayParams,
+) -> Result<BTreeMap<i64, IntradaySample>, Box<dyn Error>> {
+    let mut series = BTreeMap::new();
+    for window in params.windows() {
+        let response = get_intraday_activity(client, params, window)?;
+        series.extend(response.body.series);
+    }
+    Ok(series)
+}
@@ -6,19 +6,54 @@
 //! 1 year. The refresh token is used to get a new access token when the current access token expires.
 
 use crate::{
-    api,
+    api::client::WithingsClient,
     api::config::{load_config, write_config},
     models, redirect,
 };
 use log::{info, trace, warn};
 use random_string::generate;
 use std::collections::HashMap;
+use std::fmt;
 
 const AUTH_URL: &str = "https://account.withings.com/oauth2_user/authorize2";
 const REDIRECT_URL: &str = "http://localhost:8888";
-const API_SCOPE: &str = "user.info,user.metrics,user.activity";
 const CSRF_CHARSET: &str = "ABCDEfghiJKLnmoQRStuvWxyZ1234567890";
 const ACTION: &str = "requesttoken";
+const TOKEN_PATH: &str = "v2/oauth2";
+
+/// The scopes requested by `get_access_code`
+pub const DEFAULT_SCOPES: [Scope; 3] = [Scope::UserInfo, Scope::UserMetrics, Scope::UserActivity];
+
+/// An OAuth2 scope, the data the user is asked to share with the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// `user.info`: the user's profile, devices and goals
+    UserInfo,
+    /// `user.metrics`: measures such as weight, blood pressure and ECG
+    UserMetrics,
+    /// `user.activity`: activity, workouts and sleep summaries
+    UserActivity,
+    /// `user.sleepevents`: high frequency sleep data
+    UserSleepEvents,
+}
+
+impl Scope {
+    /// Returns the name of the scope in the API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::UserInfo => "user.info",
+            Scope::UserMetrics => "user.metrics",
+            Scope::UserActivity => "user.activity",
+            Scope::UserSleepEvents => "user.sleepevents",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
 
 /// This struct represents the parameters required for making token-related API requests.
 ///
@@ -48,7 +83,8 @@ struct TokenParams {
 /// Retrieves an authorization code from the OAuth2 authorization endpoint.
 ///
 /// This function generates an authorization URL, navigates the user to that URL for approval, and
-/// extracts the resulting authorization code once the user completes the process.
+/// extracts the resulting authorization code once the user completes the process. The user is
+/// asked for the `DEFAULT_SCOPES`, use `get_access_code_with` to request others.
 ///
 /// # Arguments
 /// - `client_id`: The app's client ID.
@@ -64,12 +100,40 @@ pub fn get_access_code(
     client_id: String,
     client_secret: String,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let auth_url = build_auth_url(&client_id, AUTH_URL, API_SCOPE, REDIRECT_URL)?;
+    get_access_code_with(
+        &WithingsClient::default(),
+        client_id,
+        client_secret,
+        &DEFAULT_SCOPES,
+    )
+}
+
+/// Retrieves an authorization code like `get_access_code`, asking the user for the given scopes
+/// and exchanging the code for an access token through the given client.
+///
+/// # Arguments
+/// - `client`: The `WithingsClient` used to call the token endpoint.
+/// - `client_id`: The app's client ID.
+/// - `client_secret`: The app's client secret.
+/// - `scopes`: The scopes to request, e.g. `DEFAULT_SCOPES` plus `Scope::UserSleepEvents` for
+///   `sleep::get_sleep`.
+///
+/// # Errors
+/// - Returns an error if the authorization process fails, or if the CSRF token validation fails.
+///
+pub fn get_access_code_with(
+    client: &WithingsClient,
+    client_id: String,
+    client_secret: String,
+    scopes: &[Scope],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let scope: Vec<&str> = scopes.iter().map(Scope::as_str).collect();
+    let auth_url = build_auth_url(&client_id, AUTH_URL, &scope.join(","), REDIRECT_URL)?;
     println!("Browse to: {}\n", auth_url);
 
     let auth_response = redirect::server::run();
     let auth_code = auth_response["code"].to_string();
-    info!("Got Auth Code: {}", auth_code);
+    info!("Got Auth Code");
 
     check_csrf_token(&auth_response["state"], &auth_url)?;
     let token_params = TokenParams {
@@ -81,7 +145,7 @@ pub fn get_access_code(
         ..Default::default()
     };
 
-    request_access_token(token_params)
+    request_access_token(client, token_params)
 }
 
 /// Refreshes an expired access token using the refresh token.
@@ -102,6 +166,26 @@ pub fn get_access_code(
 pub fn refresh_token(
     client_id: String,
     client_secret: String,
+) -> Result<String, Box<dyn std::error::Error>> {
+    refresh_token_with(&WithingsClient::default(), client_id, client_secret)
+}
+
+/// Refreshes an expired access token like `refresh_token`, calling the token endpoint through
+/// the given client.
+///
+/// # Arguments
+/// - `client`: The `WithingsClient` used to call the token endpoint.
+/// - `client_id`: The app's client ID.
+/// - `client_secret`: The app's client secret.
+///
+/// # Errors
+/// - Returns an error if the config file cannot be read, the API request fails or if parsing the
+///   response fails.
+///
+pub fn refresh_token_with(
+    client: &WithingsClient,
+    client_id: String,
+    client_secret: String,
 ) -> Result<String, Box<dyn std::error::Error>> {
     let config = load_config()?;
     let grant_type = "refresh_token".to_string();
@@ -116,42 +200,7 @@ pub fn refresh_token(
         refresh_token: Some(refresh_token),
     };
 
-    let params = prepare_token_params(token_struct);
-
-    trace!("Refresh Token API parameters: {:?}", params);
-
-    // Make the refresh token request
-    let token_url = api::wapi_url("v2/oauth2/".to_string());
-    let client = reqwest::blocking::Client::new();
-    let response = client.post(token_url).form(&params).send()?;
-
-    if response.status() != 200 {
-        warn!("Refresh API response: {:?}", response);
-        return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "API returned an error",
-        )));
-    }
-
-    // Attempt to retrieve and deserialize the response
-    let response_text = response.text()?;
-    info!("Full Response Text: {}", response_text);
-
-    // Get the access token from the response
-    let response_struct: models::OauthResponse =
-        serde_json::from_str(&response_text).map_err(|e| {
-            format!(
-                "Failed to deserialize response: {}\nResponse text: {}",
-                e, response_text
-            )
-        })?;
-
-    let access_token = response_struct.body.access_token;
-    let refresh_token = response_struct.body.refresh_token;
-    info!("Got Access Token: {}", access_token);
-
-    let _ = write_config(&access_token, &refresh_token);
-    Ok(access_token)
+    request_access_token(client, token_struct)
 }
 
 /// Prepares query parameters for API requests involving tokens.
@@ -223,10 +272,7 @@ fn build_auth_url(
 fn check_csrf_token(state: &str, expected_state: &str) -> Result<(), Box<dyn std::error::Error>> {
     if state != expected_state {
         warn!("CSRF token mismatch!");
-        return Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "CSRF token mismatch!",
-        )));
+        return Err(Box::new(std::io::Error::other("CSRF token mismatch!")));
     }
     Ok(())
 }
@@ -234,6 +280,7 @@ fn check_csrf_token(state: &str, expected_state: &str) -> Result<(), Box<dyn std
 /// Requests an access token using the provided token parameters.
 ///
 /// # Arguments
+/// - `client`: The `WithingsClient` used to call the token endpoint.
 /// - `params`: A `TokenParams` struct containing the required fields for the token request.
 ///
 /// # Returns
@@ -242,21 +289,18 @@ fn check_csrf_token(state: &str, expected_state: &str) -> Result<(), Box<dyn std
 /// # Errors
 /// - Returns an error if the API request or response parsing fails.
 ///
-fn request_access_token(params: TokenParams) -> Result<String, Box<dyn std::error::Error>> {
-    let token_url = api::wapi_url("v2/oauth2/".to_string());
+fn request_access_token(
+    client: &WithingsClient,
+    params: TokenParams,
+) -> Result<String, Box<dyn std::error::Error>> {
     let params_map = prepare_token_params(params);
-    trace!("Auth API parameters: {:?}", params_map);
-
-    let response = reqwest::blocking::Client::new()
-        .post(token_url)
-        .form(&params_map)
-        .send()?;
+    trace!("Auth API parameters: {:?}", params_map.keys());
 
-    let response_struct: models::OauthResponse = response.json()?;
+    let response_struct: models::OauthResponse = client.post_form(TOKEN_PATH, &params_map)?;
     let access_token = response_struct.body.access_token;
     let refresh_token = response_struct.body.refresh_token;
 
-    info!("Got Access Token: {}", access_token);
+    info!("Got Access Token");
     let _ = write_config(&access_token, &refresh_token);
 
     Ok(access_token)
@@ -0,0 +1,398 @@
+//! # client.rs
+//! The `WithingsClient` holds what every data endpoint call needs: the HTTP client, the API base
+//! URL and a source for the access token. The access token is sent as a `Bearer` Authorization
+//! header instead of a query parameter so it never shows up in proxy logs or in trace output.
+
+use crate::api::{self, config::load_config};
+use log::{info, trace, warn};
+use reqwest::blocking::RequestBuilder;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::{error::Error, io};
+
+/// User agent sent when the caller does not set one.
+const USER_AGENT: &str = concat!("withings-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Supplies the access token used to authorize data endpoint requests.
+///
+/// The token is requested for every call, so implementations can refresh it when it expires.
+/// Any closure returning `Result<String, Box<dyn Error>>` is a `TokenSource`, which makes it easy
+/// to plug in `auth::refresh_token`.
+pub trait TokenSource {
+    /// Returns the access token to send with the next request.
+    fn access_token(&self) -> Result<String, Box<dyn Error>>;
+}
+
+impl<F> TokenSource for F
+where
+    F: Fn() -> Result<String, Box<dyn Error>>,
+{
+    fn access_token(&self) -> Result<String, Box<dyn Error>> {
+        self()
+    }
+}
+
+/// A fixed access token, for callers that manage the token themselves.
+#[derive(Debug, Clone)]
+pub struct StaticToken(pub String);
+
+impl TokenSource for StaticToken {
+    fn access_token(&self) -> Result<String, Box<dyn Error>> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Reads the access token from the config file written by the `auth` module.
+///
+/// The file is read on every call, so a token refreshed by `auth::refresh_token` is picked up
+/// without rebuilding the client.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigFileToken;
+
+impl TokenSource for ConfigFileToken {
+    fn access_token(&self) -> Result<String, Box<dyn Error>> {
+        Ok(load_config()?.access_token)
+    }
+}
+
+/// Client for the Withings API.
+///
+/// The same HTTP transport is used for the token endpoint and the data endpoints, so timeouts,
+/// proxy and certificates set on the builder apply to every call.
+pub struct WithingsClient {
+    http: reqwest::blocking::Client,
+    base_url: String,
+    token_source: Box<dyn TokenSource>,
+}
+
+impl Default for WithingsClient {
+    fn default() -> Self {
+        Self::new(ConfigFileToken)
+    }
+}
+
+impl WithingsClient {
+    /// Creates a client that authorizes its requests with the given token source.
+    ///
+    /// # Panics
+    /// - Panics if the TLS backend cannot be initialized, like `reqwest::blocking::Client::new`.
+    pub fn new(token_source: impl TokenSource + 'static) -> Self {
+        Self::builder()
+            .token_source(token_source)
+            .build()
+            .expect("default HTTP client could not be built")
+    }
+
+    /// Returns a builder to configure the transport of the client.
+    pub fn builder() -> WithingsClientBuilder {
+        WithingsClientBuilder::default()
+    }
+
+    /// Returns the base URL the client sends its requests to.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns the URL for an API end point relative to the client's base URL.
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path)
+    }
+
+    /// Sends a GET request to a data endpoint and deserializes the JSON response.
+    ///
+    /// # Arguments
+    /// - `path`: The endpoint path relative to the API base URL, e.g. `measure`.
+    /// - `params`: The query parameters for the call. Must not contain the access token.
+    ///
+    /// # Errors
+    /// - Returns an error if the token source fails, the request fails, the API answers with an
+    ///   error status or the response cannot be parsed.
+    pub(crate) fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        params: &HashMap<&str, String>,
+    ) -> Result<T, Box<dyn Error>> {
+        trace!("{} API query parameters: {:?}", path, params);
+        let access_token = self.token_source.access_token()?;
+        let request = self
+            .http
+            .get(self.url(path))
+            .query(params)
+            .bearer_auth(access_token);
+        self.send(request)
+    }
+
+    /// Sends a form encoded POST request without an access token and deserializes the JSON
+    /// response. Used by the OAuth2 token endpoint, which authenticates with the client secret.
+    ///
+    /// # Errors
+    /// - Returns an error if the request fails, the API answers with an error status or the
+    ///   response cannot be parsed.
+    pub(crate) fn post_form<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: &HashMap<&str, String>,
+    ) -> Result<T, Box<dyn Error>> {
+        let request = self.http.post(self.url(path)).form(form);
+        self.send(request)
+    }
+
+    /// Sends the request and parses the response.
+    ///
+    /// Withings reports most failures with an HTTP 200 and a non zero `status` in the JSON
+    /// envelope, so the envelope is checked before the response is deserialized into `T`.
+    fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, Box<dyn Error>> {
+        let response = request.send()?;
+
+        if response.status().is_client_error() || response.status().is_server_error() {
+            warn!("Error response from the API: {:?}", response);
+            return Err(Box::new(io::Error::other(format!(
+                "API returned an error: {}",
+                response.status()
+            ))));
+        }
+
+        info!("Successful response from the API: {:?}", response);
+        let value: serde_json::Value = response.json().map_err(|e| {
+            warn!("Failed to parse API response: {:?}", e);
+            Box::new(e) as Box<dyn Error>
+        })?;
+
+        if let Some(error) = ApiError::from_envelope(&value) {
+            warn!("Error status from the API: {}", error);
+            return Err(Box::new(error));
+        }
+
+        serde_json::from_value(value).map_err(|e| {
+            warn!("Failed to parse API response: {:?}", e);
+            Box::new(e) as Box<dyn Error>
+        })
+    }
+}
+
+/// An error status returned by the Withings API in the `{"status": ..., "error": ...}` envelope
+/// of an otherwise successful HTTP response.
+///
+/// Callers can recover it from the boxed error with `downcast_ref::<ApiError>()`, e.g. to refresh
+/// the access token on status `401` or back off on `601`.
+///
+/// Documentation: https://developer.withings.com/api-reference#section/Response-status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    /// The non zero `status` of the response.
+    pub status: i64,
+    /// The `error` message of the response, if the API sent one.
+    pub error: Option<String>,
+}
+
+impl ApiError {
+    /// Returns the error of a response envelope, or `None` if its status is `0` or missing.
+    fn from_envelope(value: &serde_json::Value) -> Option<Self> {
+        let status = value.get("status")?.as_i64()?;
+        if status == 0 {
+            return None;
+        }
+        Some(ApiError {
+            status,
+            error: value
+                .get("error")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+        })
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.error {
+            Some(error) => write!(f, "API error status {}: {}", self.status, error),
+            None => write!(f, "API error status {}", self.status),
+        }
+    }
+}
+
+impl Error for ApiError {}
+
+/// Builder for a `WithingsClient` with custom transport options.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use withings_rs::api::WithingsClient;
+///
+/// let client = WithingsClient::builder()
+///     .timeout(Duration::from_secs(30))
+///     .proxy(reqwest::Proxy::https("http://proxy.example.com:3128").unwrap())
+///     .user_agent("weight-report/1.0")
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Default)]
+pub struct WithingsClientBuilder {
+    token_source: Option<Box<dyn TokenSource>>,
+    base_url: Option<String>,
+    http: Option<reqwest::blocking::Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxies: Vec<reqwest::Proxy>,
+    root_certificates: Vec<reqwest::Certificate>,
+    user_agent: Option<String>,
+}
+
+impl WithingsClientBuilder {
+    /// Sets the token source. Defaults to `ConfigFileToken`.
+    pub fn token_source(mut self, token_source: impl TokenSource + 'static) -> Self {
+        self.token_source = Some(Box::new(token_source));
+        self
+    }
+
+    /// Sets the base URL of the API, e.g. a local fake of the Withings API. Defaults to
+    /// `api::WAPI_BASE_URL`.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Uses a pre-built HTTP client instead of building one from the transport options.
+    ///
+    /// `build` fails if it is combined with `timeout`, `connect_timeout`, `proxy`,
+    /// `add_root_certificate` or `user_agent`, which would otherwise be silently ignored.
+    pub fn http_client(mut self, http: reqwest::blocking::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Sets the timeout for a whole request, from connecting until the response body is read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for connecting to the API.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Routes the requests through a proxy. Can be called more than once.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Trusts an additional root certificate, e.g. an internal CA. Can be called more than once.
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.root_certificates.push(certificate);
+        self
+    }
+
+    /// Sets the User-Agent header. Defaults to `withings-rs/<version>`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Builds the client.
+    ///
+    /// # Errors
+    /// - Returns an error if `http_client` is combined with another transport option.
+    /// - Returns an error if the HTTP client cannot be built, e.g. the TLS backend fails to
+    ///   initialize.
+    pub fn build(mut self) -> Result<WithingsClient, Box<dyn Error>> {
+        let http = self.build_http()?;
+
+        let base_url = self
+            .base_url
+            .unwrap_or_else(|| api::WAPI_BASE_URL.to_string());
+
+        Ok(WithingsClient {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token_source: self
+                .token_source
+                .unwrap_or_else(|| Box::new(ConfigFileToken)),
+        })
+    }
+
+    /// Builds the HTTP client from the transport options, or returns the one set with
+    /// `http_client`. The token source and base URL are left in the builder.
+    ///
+    /// # Errors
+    /// - Returns an error if `http_client` is combined with another transport option, or if the
+    ///   HTTP client cannot be built.
+    pub(crate) fn build_http(&mut self) -> Result<reqwest::blocking::Client, Box<dyn Error>> {
+        let has_transport_options = self.timeout.is_some()
+            || self.connect_timeout.is_some()
+            || !self.proxies.is_empty()
+            || !self.root_certificates.is_empty()
+            || self.user_agent.is_some();
+
+        if let Some(http) = self.http.take() {
+            if has_transport_options {
+                return Err(Box::new(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "http_client cannot be combined with timeout, connect_timeout, proxy, \
+                     add_root_certificate or user_agent",
+                )));
+            }
+            return Ok(http);
+        }
+
+        let mut builder = reqwest::blocking::Client::builder().user_agent(
+            self.user_agent
+                .take()
+                .unwrap_or_else(|| USER_AGENT.to_string()),
+        );
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        for proxy in self.proxies.drain(..) {
+            builder = builder.proxy(proxy);
+        }
+        for certificate in self.root_certificates.drain(..) {
+            builder = builder.add_root_certificate(certificate);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_client_alone_is_used() {
+        let client = WithingsClient::builder()
+            .http_client(reqwest::blocking::Client::new())
+            .base_url("http://127.0.0.1:8080/")
+            .build()
+            .unwrap();
+        assert_eq!(client.base_url(), "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn http_client_with_transport_options_is_rejected() {
+        let result = WithingsClient::builder()
+            .http_client(reqwest::blocking::Client::new())
+            .timeout(Duration::from_secs(5))
+            .build();
+        assert!(result.is_err());
+
+        let result = WithingsClient::builder()
+            .user_agent("weight-report/1.0")
+            .http_client(reqwest::blocking::Client::new())
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn base_url_defaults_to_the_withings_api() {
+        let client = WithingsClient::builder().build().unwrap();
+        assert_eq!(client.base_url(), api::WAPI_BASE_URL);
+    }
+}
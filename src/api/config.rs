@@ -24,7 +24,8 @@ pub fn get_config_file() -> String {
 /// Serializes an object and writes it to a file.
 ///
 /// This function takes any object that implements the `serde::Serialize` trait,
-/// serializes the object to JSON, and writes it to the provided file path.
+/// serializes the object to JSON, and writes it to the provided file path. The JSON is written to
+/// a temporary file first and renamed into place, so the file is replaced atomically.
 ///
 /// # Arguments
 /// - `file_path`: The path where the serialized object will be written.
@@ -34,12 +35,24 @@ pub fn get_config_file() -> String {
 /// - `Ok(())` on success.
 /// - An error wrapped in `Result` if file creation or serialization fails.
 ///
-fn save_to_file<T: serde::Serialize>(
+pub(crate) fn save_to_file<T: serde::Serialize>(
     file_path: &str,
     object: &T,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let file = std::fs::File::create(file_path)?;
-    serde_json::to_writer_pretty(file, object)?;
+    // Written next to the target and renamed over it, so a crash or a concurrent reader never
+    // sees a truncated file.
+    let tmp_path = format!("{}.tmp", file_path);
+    let write = || -> Result<(), Box<dyn std::error::Error>> {
+        let file = std::fs::File::create(&tmp_path)?;
+        serde_json::to_writer_pretty(&file, object)?;
+        file.sync_all()?;
+        Ok(())
+    };
+    if let Err(e) = write() {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(&tmp_path, file_path)?;
     Ok(())
 }
 
@@ -55,7 +68,7 @@ fn save_to_file<T: serde::Serialize>(
 /// - On success, returns the deserialized object of type `T`.
 /// - An error wrapped in `Result` if file reading or JSON deserialization fails.
 ///
-fn read_from_file<T: serde::de::DeserializeOwned>(
+pub(crate) fn read_from_file<T: serde::de::DeserializeOwned>(
     file_path: &str,
 ) -> Result<T, Box<dyn std::error::Error>> {
     let file = std::fs::File::open(file_path)?;
@@ -79,12 +92,12 @@ fn read_from_file<T: serde::de::DeserializeOwned>(
 ///
 // Usage in `write_config`
 pub fn write_config(
-    access_token: &String,
-    refresh_token: &String,
+    access_token: &str,
+    refresh_token: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let config = models::Config {
-        access_token: access_token.clone(),
-        refresh_token: refresh_token.clone(),
+        access_token: access_token.to_string(),
+        refresh_token: refresh_token.to_string(),
     };
     save_to_file(&get_config_file(), &config)
 }
@@ -0,0 +1,258 @@
+//! # heart.rs
+//! Calls the Withings API to get the ECG recordings and their signal
+//! https://developer.withings.com/api-reference/#tag/heart
+
+use crate::api::client::WithingsClient;
+use crate::api::pagination::{next_offset, Items, Pages};
+use crate::api::params::{epoch_seconds, DateRange, ParamsError};
+use crate::models::heart::{HeartRecording, ResponseHeartList, ResponseHeartSignal};
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::SystemTime;
+
+const HEART_V2_PATH: &str = "v2/heart";
+
+/// Represents the parameters for a list of ECG recordings.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use withings_rs::api::heart::HeartListParams;
+///
+/// let params = HeartListParams::builder()
+///     .startdate(UNIX_EPOCH + Duration::from_secs(1_704_067_200))
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.to_query_params()["startdate"], "1704067200");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HeartListParams {
+    start: Option<i64>,
+    end: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl HeartListParams {
+    /// Returns a builder for the list parameters.
+    pub fn builder() -> HeartListParamsBuilder {
+        HeartListParamsBuilder::default()
+    }
+
+    /// Converts the `HeartListParams` struct into a `HashMap` of request parameters.
+    pub fn to_query_params(&self) -> HashMap<&str, String> {
+        let mut params = HashMap::new();
+
+        params.insert("action", "list".to_string());
+
+        if let Some(start) = self.start {
+            params.insert("startdate", start.to_string());
+        }
+        if let Some(end) = self.end {
+            params.insert("enddate", end.to_string());
+        }
+        if let Some(offset) = self.offset {
+            params.insert("offset", offset.to_string());
+        }
+
+        params
+    }
+
+    /// Returns a copy of the parameters that requests the page at `offset`.
+    pub fn with_offset(&self, offset: i64) -> Self {
+        HeartListParams {
+            offset: Some(offset),
+            ..self.clone()
+        }
+    }
+}
+
+/// Builder for `HeartListParams`.
+#[derive(Debug, Default)]
+pub struct HeartListParamsBuilder {
+    start: Option<SystemTime>,
+    end: Option<SystemTime>,
+    offset: Option<i64>,
+}
+
+impl HeartListParamsBuilder {
+    /// Only returns recordings made at or after `start`.
+    pub fn startdate(mut self, start: impl Into<SystemTime>) -> Self {
+        self.start = Some(start.into());
+        self
+    }
+
+    /// Only returns recordings made at or before `end`.
+    pub fn enddate(mut self, end: impl Into<SystemTime>) -> Self {
+        self.end = Some(end.into());
+        self
+    }
+
+    /// Sets both `startdate` and `enddate` from a range.
+    pub fn date_range(self, range: DateRange) -> Self {
+        self.startdate(range.start()).enddate(range.end())
+    }
+
+    /// Sets the offset returned by a previous response to fetch the next page.
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Validates and builds the parameters.
+    ///
+    /// # Errors
+    /// - Returns `ParamsError::NegativeOffset` if the offset is negative.
+    /// - Returns `ParamsError::StartAfterEnd` if `startdate` is after `enddate`.
+    /// - Returns `ParamsError::BeforeEpoch` if a timestamp is before the Unix epoch.
+    pub fn build(self) -> Result<HeartListParams, ParamsError> {
+        if let Some(offset) = self.offset.filter(|offset| *offset < 0) {
+            return Err(ParamsError::NegativeOffset(offset));
+        }
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            DateRange::new(start, end)?;
+        }
+
+        Ok(HeartListParams {
+            start: self.start.map(epoch_seconds).transpose()?,
+            end: self.end.map(epoch_seconds).transpose()?,
+            offset: self.offset,
+        })
+    }
+}
+
+/// Retrieves one page of ECG recordings from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `params` - The `HeartListParams` struct containing the parameters for the API call.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::heart::ResponseHeartList` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/heart/operation/heartv2-list
+pub fn get_recording_page(
+    client: &WithingsClient,
+    params: &HeartListParams,
+) -> Result<ResponseHeartList, Box<dyn Error>> {
+    client.get(HEART_V2_PATH, &params.to_query_params())
+}
+
+/// Returns an iterator over the pages of ECG recordings, following the `offset` returned by each
+/// response.
+pub fn get_recording_pages<'a>(
+    client: &'a WithingsClient,
+    params: &HeartListParams,
+) -> Pages<'a, ResponseHeartList> {
+    let mut params = params.clone();
+    Pages::new(move |offset| {
+        if let Some(offset) = offset {
+            params = params.with_offset(offset);
+        }
+        let response = get_recording_page(client, &params)?;
+        let next = next_offset(response.body.more, response.body.offset);
+        Ok((response, next))
+    })
+}
+
+/// Returns an iterator over every ECG recording, across all pages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{heart, WithingsClient};
+/// use withings_rs::models::heart::AfibClassification;
+///
+/// let client = WithingsClient::default();
+/// let params = heart::HeartListParams::default();
+/// for recording in heart::get_recordings(&client, &params) {
+///     let recording = recording.unwrap();
+///     if recording.ecg.afib == AfibClassification::Positive {
+///         let signal = heart::get_signal(&client, recording.ecg.signalid).unwrap().body;
+///         println!("{} samples at {} Hz", signal.signal.len(), signal.sampling_frequency);
+///     }
+/// }
+/// ```
+pub fn get_recordings<'a>(
+    client: &'a WithingsClient,
+    params: &HeartListParams,
+) -> Items<'a, ResponseHeartList, HeartRecording> {
+    get_recording_pages(client, params).items(|response| response.body.series)
+}
+
+/// Retrieves the signal of an ECG recording from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `signalid` - The `signalid` of the recording, from `get_recording_page`.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::heart::ResponseHeartSignal` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/heart/operation/heartv2-get
+pub fn get_signal(
+    client: &WithingsClient,
+    signalid: i64,
+) -> Result<ResponseHeartSignal, Box<dyn Error>> {
+    let mut params = HashMap::new();
+    params.insert("action", "get".to_string());
+    params.insert("signalid", signalid.to_string());
+    client.get(HEART_V2_PATH, &params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn build_rejects_a_negative_offset() {
+        let result = HeartListParams::builder().offset(-1).build();
+        assert_eq!(result.unwrap_err(), ParamsError::NegativeOffset(-1));
+        let params = HeartListParams::builder().offset(0).build().unwrap();
+        assert_eq!(params.to_query_params()["offset"], "0");
+    }
+
+    #[test]
+    fn build_rejects_start_after_end() {
+        let result = HeartListParams::builder()
+            .startdate(at(20))
+            .enddate(at(10))
+            .build();
+        assert_eq!(result.unwrap_err(), ParamsError::StartAfterEnd);
+    }
+
+    #[test]
+    fn build_rejects_times_before_the_epoch() {
+        let before = UNIX_EPOCH - Duration::from_secs(1);
+        let result = HeartListParams::builder().startdate(before).build();
+        assert_eq!(result.unwrap_err(), ParamsError::BeforeEpoch);
+        let result = HeartListParams::builder().enddate(before).build();
+        assert_eq!(result.unwrap_err(), ParamsError::BeforeEpoch);
+    }
+
+    #[test]
+    fn with_offset_only_changes_the_offset() {
+        let params = HeartListParams::builder()
+            .startdate(at(10))
+            .enddate(at(20))
+            .build()
+            .unwrap()
+            .with_offset(3);
+        let query = params.to_query_params();
+        assert_eq!(query["action"], "list");
+        assert_eq!(query["startdate"], "10");
+        assert_eq!(query["enddate"], "20");
+        assert_eq!(query["offset"], "3");
+    }
+}
@@ -1,58 +1,241 @@
 //! # measure.rs
-//! Calls the Withings API to get the list of measurements
+//! Calls the Withings API to get the list of measurements and workouts
 //! https://developer.withings.com/oauth2/#operation/measure-getmeas
+//! https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getworkouts
 
-use crate::{api, models};
-use log::{info, trace, warn};
+use crate::api::client::WithingsClient;
+use crate::api::pagination::{next_offset, Items, Pages};
+use crate::api::params::{
+    epoch_seconds, DataField, DateRange, DayRangeParams, DayRangeParamsBuilder, ParamsError,
+};
+use crate::models::workout::{ResponseWorkouts, Workout, WorkoutField};
+use crate::models::{self, meas::CategoryType, MeasureType};
 use std::collections::HashMap;
-use std::{error::Error, io};
+use std::error::Error;
+use std::time::SystemTime;
+
+/// Path of the v2 measure endpoints: activity, intraday activity and workouts.
+pub(crate) const MEASURE_V2_PATH: &str = "v2/measure";
 
 /// Represents the parameters for a measurement request.
-#[derive(Debug)]
+/// The access token is not part of the parameters, it is supplied by the client's token source.
+///
+/// Built with `MeasurementParams::builder()`, which validates the parameters before anything is
+/// sent.
+///
+/// # Examples
+///
+/// ```
+/// use withings_rs::api::measure::MeasurementParams;
+/// use withings_rs::models::{meas::CategoryType, MeasureType};
+///
+/// let params = MeasurementParams::builder()
+///     .meastypes([MeasureType::Weight, MeasureType::FatRatio])
+///     .category(CategoryType::Measures)
+///     .lastupdate(std::time::UNIX_EPOCH)
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.to_query_params()["meastypes"], "1,6");
+/// ```
+#[derive(Debug, Clone)]
 pub struct MeasurementParams {
-    pub access_token: String,
-    pub client_id: String,
-    pub meastype: String,
-    pub category: String,
-    pub start: Option<String>,
-    pub end: Option<String>,
-    pub offset: Option<String>,
-    pub lastupdate: Option<String>,
+    client_id: Option<String>,
+    meastypes: Vec<MeasureType>,
+    category: Option<CategoryType>,
+    start: Option<i64>,
+    end: Option<i64>,
+    offset: Option<i64>,
+    lastupdate: Option<i64>,
 }
 
 impl MeasurementParams {
+    /// Returns a builder for the measurement parameters.
+    pub fn builder() -> MeasurementParamsBuilder {
+        MeasurementParamsBuilder::default()
+    }
+
     /// Converts the `MeasurementParams` struct into a `HashMap` of request parameters.
     pub fn to_query_params(&self) -> HashMap<&str, String> {
         let mut params = HashMap::new();
 
-        params.insert("client_id", self.client_id.clone());
         params.insert("action", "getmeas".to_string());
-        params.insert("access_token", self.access_token.clone());
-        params.insert("meastype", self.meastype.clone());
-        params.insert("category", self.category.clone());
 
         // Add optional parameters if provided.
-        if let Some(start) = &self.start {
-            params.insert("startdate", start.clone());
+        if let Some(client_id) = &self.client_id {
+            params.insert("client_id", client_id.clone());
         }
-        if let Some(end) = &self.end {
-            params.insert("enddate", end.clone());
+        match self.meastypes.as_slice() {
+            [] => {}
+            [meastype] => {
+                params.insert("meastype", meastype.to_string());
+            }
+            meastypes => {
+                let meastypes: Vec<String> = meastypes.iter().map(|t| t.to_string()).collect();
+                params.insert("meastypes", meastypes.join(","));
+            }
         }
-        if let Some(offset) = &self.offset {
-            params.insert("offset", offset.clone());
+        if let Some(category) = &self.category {
+            params.insert("category", category.to_string());
         }
-        if let Some(lastupdate) = &self.lastupdate {
-            params.insert("lastupdate", lastupdate.clone());
+        if let Some(start) = self.start {
+            params.insert("startdate", start.to_string());
+        }
+        if let Some(end) = self.end {
+            params.insert("enddate", end.to_string());
+        }
+        if let Some(offset) = self.offset {
+            params.insert("offset", offset.to_string());
+        }
+        if let Some(lastupdate) = self.lastupdate {
+            params.insert("lastupdate", lastupdate.to_string());
         }
 
         params
     }
+
+    /// Returns the `startdate` as epoch seconds.
+    pub fn startdate(&self) -> Option<i64> {
+        self.start
+    }
+
+    /// Returns the `enddate` as epoch seconds.
+    pub fn enddate(&self) -> Option<i64> {
+        self.end
+    }
+
+    /// Returns the `lastupdate` as epoch seconds.
+    pub fn lastupdate(&self) -> Option<i64> {
+        self.lastupdate
+    }
+
+    /// Returns a copy of the parameters that only asks for changes since `lastupdate`, as epoch
+    /// seconds.
+    ///
+    /// # Errors
+    /// - Returns `ParamsError::LastUpdateWithDateRange` if the parameters have a date range.
+    pub fn with_lastupdate(&self, lastupdate: i64) -> Result<Self, ParamsError> {
+        if self.start.is_some() || self.end.is_some() {
+            return Err(ParamsError::LastUpdateWithDateRange);
+        }
+        Ok(MeasurementParams {
+            lastupdate: Some(lastupdate),
+            offset: None,
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy of the parameters that requests the page at `offset`.
+    pub fn with_offset(&self, offset: i64) -> Self {
+        MeasurementParams {
+            offset: Some(offset),
+            ..self.clone()
+        }
+    }
+}
+
+/// Builder for `MeasurementParams`.
+#[derive(Debug, Default)]
+pub struct MeasurementParamsBuilder {
+    client_id: Option<String>,
+    meastypes: Vec<MeasureType>,
+    category: Option<CategoryType>,
+    start: Option<SystemTime>,
+    end: Option<SystemTime>,
+    offset: Option<i64>,
+    lastupdate: Option<SystemTime>,
+}
+
+impl MeasurementParamsBuilder {
+    /// Sets the client ID sent with the request.
+    pub fn client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    /// Adds a measure type to request. Can be called more than once.
+    pub fn meastype(mut self, meastype: MeasureType) -> Self {
+        self.meastypes.push(meastype);
+        self
+    }
+
+    /// Adds several measure types to request, sent as the `meastypes` parameter.
+    pub fn meastypes(mut self, meastypes: impl IntoIterator<Item = MeasureType>) -> Self {
+        self.meastypes.extend(meastypes);
+        self
+    }
+
+    /// Sets the category of the measures: real measures or user objectives.
+    pub fn category(mut self, category: CategoryType) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Only returns measures taken at or after `start`.
+    pub fn startdate(mut self, start: impl Into<SystemTime>) -> Self {
+        self.start = Some(start.into());
+        self
+    }
+
+    /// Only returns measures taken at or before `end`.
+    pub fn enddate(mut self, end: impl Into<SystemTime>) -> Self {
+        self.end = Some(end.into());
+        self
+    }
+
+    /// Sets both `startdate` and `enddate` from a range.
+    pub fn date_range(self, range: DateRange) -> Self {
+        self.startdate(range.start()).enddate(range.end())
+    }
+
+    /// Only returns measures created or modified since `lastupdate`.
+    /// Cannot be combined with `startdate`/`enddate`.
+    pub fn lastupdate(mut self, lastupdate: impl Into<SystemTime>) -> Self {
+        self.lastupdate = Some(lastupdate.into());
+        self
+    }
+
+    /// Sets the offset returned by a previous response to fetch the next page.
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Validates and builds the parameters.
+    ///
+    /// # Errors
+    /// - Returns `ParamsError::LastUpdateWithDateRange` if `lastupdate` is combined with
+    ///   `startdate` or `enddate`.
+    /// - Returns `ParamsError::StartAfterEnd` if `startdate` is after `enddate`.
+    /// - Returns `ParamsError::BeforeEpoch` if a timestamp is before the Unix epoch.
+    /// - Returns `ParamsError::NegativeOffset` if the offset is negative.
+    pub fn build(self) -> Result<MeasurementParams, ParamsError> {
+        if let Some(offset) = self.offset.filter(|offset| *offset < 0) {
+            return Err(ParamsError::NegativeOffset(offset));
+        }
+        if self.lastupdate.is_some() && (self.start.is_some() || self.end.is_some()) {
+            return Err(ParamsError::LastUpdateWithDateRange);
+        }
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            DateRange::new(start, end)?;
+        }
+
+        Ok(MeasurementParams {
+            client_id: self.client_id,
+            meastypes: self.meastypes,
+            category: self.category,
+            start: self.start.map(epoch_seconds).transpose()?,
+            end: self.end.map(epoch_seconds).transpose()?,
+            offset: self.offset,
+            lastupdate: self.lastupdate.map(epoch_seconds).transpose()?,
+        })
+    }
 }
 
 /// Retrieves measurements from the Withings API based on the provided parameters.
 ///
 /// # Arguments
 ///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
 /// * `params` - The `MeasurementParams` struct containing the parameters for the API call.
 ///
 /// # Returns
@@ -62,33 +245,219 @@ impl MeasurementParams {
 /// # Documentation
 /// https://developer.withings.com/api-reference/#tag/measure
 pub fn get_measurements(
+    client: &WithingsClient,
     params: &MeasurementParams,
 ) -> Result<models::meas::ResponseMeas, Box<dyn Error>> {
-    // Step 1: Prepare the parameters for the API call
-    let query_params = params.to_query_params();
-    trace!("Measure API query parameters: {:?}", query_params);
-
-    // Step 2: Prepare the API call
-    let client = reqwest::blocking::Client::new();
-    let url = api::wapi_url("measure".to_string());
-
-    // Step 3: Make the API request
-    let response = client.get(&url).query(&query_params).send()?;
-
-    // Step 4: Handle response errors
-    if response.status().is_client_error() || response.status().is_server_error() {
-        warn!("Error response from the API: {:?}", response);
-        return Err(Box::new(io::Error::new(
-            io::ErrorKind::Other,
-            format!("API returned an error: {}", response.status()),
-        )));
-    }
-
-    // Step 5: Parse the JSON response
-    info!("Successful response from Measure API: {:?}", response);
-    response.json::<models::meas::ResponseMeas>().map_err(|e| {
-        // Convert serde JSON parsing errors into a compatible error
-        warn!("Failed to parse API response: {:?}", e);
-        Box::new(e) as Box<dyn Error>
+    client.get("measure", &params.to_query_params())
+}
+
+/// Returns an iterator over the pages of a measurement request.
+///
+/// The first page is requested with `params`, then the `offset` returned by each response is
+/// followed until Withings reports that there are no more measure groups, or returns an offset
+/// that does not advance.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the requests.
+/// * `params` - The parameters of the first request.
+pub fn get_measurement_pages<'a>(
+    client: &'a WithingsClient,
+    params: &MeasurementParams,
+) -> Pages<'a, models::meas::ResponseMeas> {
+    let mut params = params.clone();
+    Pages::new(move |offset| {
+        if let Some(offset) = offset {
+            params = params.with_offset(offset);
+        }
+        let response = get_measurements(client, &params)?;
+        let next = next_offset(response.body.more, response.body.offset);
+        Ok((response, next))
     })
 }
+
+/// Returns an iterator over every measure group of a measurement request, across all pages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{measure, WithingsClient};
+/// use withings_rs::models::MeasureType;
+///
+/// let client = WithingsClient::default();
+/// let params = measure::MeasurementParams::builder()
+///     .meastype(MeasureType::Weight)
+///     .build()
+///     .unwrap();
+/// for group in measure::get_measure_groups(&client, &params).max_pages(10) {
+///     let group = group.unwrap();
+///     println!("{} measures", group.measures.len());
+/// }
+/// ```
+pub fn get_measure_groups<'a>(
+    client: &'a WithingsClient,
+    params: &MeasurementParams,
+) -> Items<'a, models::meas::ResponseMeas, models::Measuregrp> {
+    get_measurement_pages(client, params).items(|response| response.body.measuregrps)
+}
+
+/// Represents the parameters for a workouts request.
+///
+/// Either a range of days or `lastupdate` must be set.
+///
+/// # Examples
+///
+/// ```
+/// use withings_rs::api::measure::WorkoutParams;
+/// use withings_rs::models::workout::WorkoutField;
+///
+/// let params = WorkoutParams::builder()
+///     .lastupdate(std::time::UNIX_EPOCH)
+///     .data_fields([WorkoutField::Calories, WorkoutField::HrAverage])
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.to_query_params()["data_fields"], "calories,hr_average");
+/// ```
+pub type WorkoutParams = DayRangeParams<WorkoutField>;
+
+/// Builder for `WorkoutParams`.
+pub type WorkoutParamsBuilder = DayRangeParamsBuilder<WorkoutField>;
+
+impl DataField for WorkoutField {
+    const ACTION: &'static str = "getworkouts";
+
+    fn name(&self) -> &'static str {
+        WorkoutField::name(self)
+    }
+}
+
+/// Retrieves one page of workouts from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `params` - The `WorkoutParams` struct containing the parameters for the API call.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::workout::ResponseWorkouts` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getworkouts
+pub fn get_workout_page(
+    client: &WithingsClient,
+    params: &WorkoutParams,
+) -> Result<ResponseWorkouts, Box<dyn Error>> {
+    client.get(MEASURE_V2_PATH, &params.to_query_params())
+}
+
+/// Returns an iterator over the pages of a workouts request, following the `offset` returned by
+/// each response.
+pub fn get_workout_pages<'a>(
+    client: &'a WithingsClient,
+    params: &WorkoutParams,
+) -> Pages<'a, ResponseWorkouts> {
+    let mut params = params.clone();
+    Pages::new(move |offset| {
+        if let Some(offset) = offset {
+            params = params.with_offset(offset);
+        }
+        let response = get_workout_page(client, &params)?;
+        let next = next_offset(response.body.more, response.body.offset);
+        Ok((response, next))
+    })
+}
+
+/// Returns an iterator over every workout of a request, across all pages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{measure, WithingsClient};
+/// use withings_rs::models::workout::WorkoutCategory;
+///
+/// let client = WithingsClient::default();
+/// let params = measure::WorkoutParams::builder()
+///     .lastupdate(std::time::UNIX_EPOCH)
+///     .build()
+///     .unwrap();
+/// for workout in measure::get_workouts(&client, &params) {
+///     let workout = workout.unwrap();
+///     if workout.category == WorkoutCategory::Run {
+///         println!("{:?} m in {:?}", workout.data.distance, workout.duration());
+///     }
+/// }
+/// ```
+pub fn get_workouts<'a>(
+    client: &'a WithingsClient,
+    params: &WorkoutParams,
+) -> Items<'a, ResponseWorkouts, Workout> {
+    get_workout_pages(client, params).items(|response| response.body.series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn build_rejects_a_negative_offset() {
+        let result = MeasurementParams::builder().offset(-1).build();
+        assert_eq!(result.unwrap_err(), ParamsError::NegativeOffset(-1));
+        assert!(MeasurementParams::builder().offset(0).build().is_ok());
+    }
+
+    #[test]
+    fn with_offset_only_changes_the_offset() {
+        let params = MeasurementParams::builder()
+            .meastype(MeasureType::Weight)
+            .lastupdate(at(10))
+            .offset(1)
+            .build()
+            .unwrap();
+        let next = params.with_offset(2);
+        assert_eq!(next.to_query_params()["offset"], "2");
+        assert_eq!(next.lastupdate(), Some(10));
+        assert_eq!(next.to_query_params()["meastype"], "1");
+        assert_eq!(params.to_query_params()["offset"], "1");
+    }
+
+    #[test]
+    fn build_rejects_lastupdate_with_a_date_range() {
+        let result = MeasurementParams::builder()
+            .startdate(at(10))
+            .lastupdate(at(20))
+            .build();
+        assert_eq!(result.unwrap_err(), ParamsError::LastUpdateWithDateRange);
+
+        let params = MeasurementParams::builder()
+            .enddate(at(10))
+            .build()
+            .unwrap();
+        assert_eq!(
+            params.with_lastupdate(20).unwrap_err(),
+            ParamsError::LastUpdateWithDateRange
+        );
+    }
+
+    #[test]
+    fn build_rejects_start_after_end() {
+        let result = MeasurementParams::builder()
+            .startdate(at(20))
+            .enddate(at(10))
+            .build();
+        assert_eq!(result.unwrap_err(), ParamsError::StartAfterEnd);
+    }
+
+    #[test]
+    fn build_rejects_times_before_the_epoch() {
+        let result = MeasurementParams::builder()
+            .lastupdate(UNIX_EPOCH - Duration::from_secs(1))
+            .build();
+        assert_eq!(result.unwrap_err(), ParamsError::BeforeEpoch);
+    }
+}
@@ -1,12 +1,30 @@
 //! # Withings API
 //! Calls the withings API end points
 //! Documentation: https://developer.withings.com/api-reference
+//!
+//! Paginated endpoints expose `get_<item>_page` for a single request, `get_<item>_pages` to
+//! follow the offsets across every page, and `get_<items>` to iterate over every item.
+//! `measure::get_measurements` keeps its historical name for the single getmeas request.
+pub mod activity;
 pub mod auth;
+pub mod client;
 pub mod config;
+pub mod heart;
 pub mod measure;
+pub mod notify;
+pub mod pagination;
+pub mod params;
+pub mod sleep;
+pub mod stetho;
+pub mod user;
+
+pub use self::client::{ApiError, WithingsClient, WithingsClientBuilder};
+
+/// Base URL of the Withings API
+pub const WAPI_BASE_URL: &str = "https://wbsapi.withings.net";
 
 /// wapi_url
 /// Returns the URL for the withings API end point
 pub fn wapi_url(path: String) -> String {
-    format!("https://wbsapi.withings.net/{}", path)
+    format!("{}/{}", WAPI_BASE_URL, path)
 }
@@ -0,0 +1,345 @@
+//! # notify.rs
+//! Calls the Withings API to manage the notification subscriptions. Withings calls the callback
+//! URL of a subscription when new data is available, instead of the app polling for it.
+//! https://developer.withings.com/api-reference/#tag/notify
+//!
+//! When subscribing, Withings verifies the callback URL by calling it and expects a `200` answer
+//! within a few seconds. A failed verification is reported as
+//! `NotifyError::CallbackVerificationFailed`.
+
+use crate::api::client::{ApiError, WithingsClient};
+use crate::models::notify::{Appli, Subscription, SubscriptionsBody};
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::net::IpAddr;
+use url::Url;
+
+const NOTIFY_PATH: &str = "notify";
+
+/// An error from the notify endpoints.
+#[derive(Debug)]
+pub enum NotifyError {
+    /// The callback URL was rejected before anything was sent, with the reason.
+    InvalidCallbackUrl(String),
+    /// Withings could not verify the callback URL: it is unreachable or did not answer `200`.
+    CallbackVerificationFailed(String),
+    /// No subscription exists for the callback URL and `appli`.
+    NoSuchSubscription,
+    /// The comment is missing or invalid.
+    InvalidComment,
+    /// The user already has as many subscriptions as Withings allows.
+    TooManySubscriptions,
+    /// Any other error status returned by Withings, with its message.
+    Api { status: i64, error: Option<String> },
+    /// The request failed or the response could not be parsed.
+    Request(Box<dyn Error>),
+}
+
+impl NotifyError {
+    /// Maps a non-zero `status` returned by the notify endpoints to an error.
+    fn from_status(status: i64, error: Option<String>) -> Self {
+        match status {
+            293 => NotifyError::CallbackVerificationFailed(
+                error.unwrap_or_else(|| "the callback URL is absent or incorrect".to_string()),
+            ),
+            294 | 343 => NotifyError::NoSuchSubscription,
+            304 => NotifyError::InvalidComment,
+            305 => NotifyError::TooManySubscriptions,
+            status => NotifyError::Api { status, error },
+        }
+    }
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotifyError::InvalidCallbackUrl(reason) => {
+                write!(f, "invalid callback URL: {}", reason)
+            }
+            NotifyError::CallbackVerificationFailed(reason) => {
+                write!(f, "Withings could not verify the callback URL: {}", reason)
+            }
+            NotifyError::NoSuchSubscription => write!(f, "no such subscription"),
+            NotifyError::InvalidComment => write!(f, "the comment is absent or incorrect"),
+            NotifyError::TooManySubscriptions => write!(f, "too many subscriptions"),
+            NotifyError::Api { status, error } => match error {
+                Some(error) => write!(f, "API returned status {}: {}", status, error),
+                None => write!(f, "API returned status {}", status),
+            },
+            NotifyError::Request(e) => write!(f, "request failed: {}", e),
+        }
+    }
+}
+
+impl Error for NotifyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NotifyError::Request(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that Withings can call a callback URL: an absolute `http` or `https` URL with a host
+/// that is not the local machine.
+///
+/// # Examples
+///
+/// ```
+/// use withings_rs::api::notify::validate_callback_url;
+///
+/// assert!(validate_callback_url("https://example.com/withings").is_ok());
+/// assert!(validate_callback_url("http://localhost:8888").is_err());
+/// assert!(validate_callback_url("ftp://example.com").is_err());
+/// ```
+///
+/// # Errors
+/// - Returns `NotifyError::InvalidCallbackUrl` with the reason if the URL cannot be used.
+pub fn validate_callback_url(callbackurl: &str) -> Result<Url, NotifyError> {
+    let invalid = |reason: &str| NotifyError::InvalidCallbackUrl(reason.to_string());
+    let url = Url::parse(callbackurl).map_err(|e| invalid(&e.to_string()))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("the scheme must be http or https"));
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| invalid("the URL has no host"))?;
+    let is_loopback = host
+        .trim_matches(|c| c == '[' || c == ']')
+        .parse::<IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback() || ip.is_unspecified());
+    if is_loopback || host.eq_ignore_ascii_case("localhost") {
+        return Err(invalid("Withings cannot reach the local machine"));
+    }
+    Ok(url)
+}
+
+/// The notify response envelope. Error statuses are rejected by the client before it is parsed.
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(default)]
+    body: serde_json::Value,
+}
+
+/// Sends a notify request and returns its body, or the typed error for a non-zero status.
+fn call<T: DeserializeOwned>(
+    client: &WithingsClient,
+    params: &HashMap<&str, String>,
+) -> Result<T, NotifyError> {
+    let envelope: Envelope =
+        client
+            .get(NOTIFY_PATH, params)
+            .map_err(|e| match e.downcast::<ApiError>() {
+                Ok(api_error) => {
+                    warn!(
+                        "Notify error status {}: {:?}",
+                        api_error.status, api_error.error
+                    );
+                    NotifyError::from_status(api_error.status, api_error.error)
+                }
+                Err(e) => NotifyError::Request(e),
+            })?;
+    serde_json::from_value(envelope.body).map_err(|e| NotifyError::Request(Box::new(e)))
+}
+
+/// Subscribes a callback URL to the notifications of `appli`.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `callbackurl` - The URL Withings calls when new data is available.
+/// * `appli` - The kind of data to be notified about.
+/// * `comment` - A description of the subscription.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{notify, WithingsClient};
+/// use withings_rs::models::notify::Appli;
+///
+/// let client = WithingsClient::default();
+/// match notify::subscribe(&client, "https://example.com/withings", Appli::Weight, "weight") {
+///     Err(notify::NotifyError::CallbackVerificationFailed(reason)) => {
+///         eprintln!("make sure the callback answers 200: {}", reason)
+///     }
+///     result => result.unwrap(),
+/// }
+/// ```
+///
+/// # Errors
+/// - Returns `NotifyError::InvalidCallbackUrl` if the URL cannot be used, without sending
+///   anything.
+/// - Returns `NotifyError::CallbackVerificationFailed` if Withings could not verify the URL.
+/// - Returns another `NotifyError` if the request fails or Withings returns an error status.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/notify/operation/notify-subscribe
+pub fn subscribe(
+    client: &WithingsClient,
+    callbackurl: &str,
+    appli: Appli,
+    comment: &str,
+) -> Result<(), NotifyError> {
+    // The URL is sent as given, get and revoke must match it exactly.
+    validate_callback_url(callbackurl)?;
+    let mut params = HashMap::new();
+    params.insert("action", "subscribe".to_string());
+    params.insert("callbackurl", callbackurl.to_string());
+    params.insert("appli", appli.to_string());
+    params.insert("comment", comment.to_string());
+    call::<serde_json::Value>(client, &params).map(|_| ())
+}
+
+/// Lists the subscriptions of the user, only those of `appli` if set.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/notify/operation/notify-list
+pub fn list_subscriptions(
+    client: &WithingsClient,
+    appli: Option<Appli>,
+) -> Result<Vec<Subscription>, NotifyError> {
+    let mut params = HashMap::new();
+    params.insert("action", "list".to_string());
+    if let Some(appli) = appli {
+        params.insert("appli", appli.to_string());
+    }
+    call::<SubscriptionsBody>(client, &params).map(|body| body.profiles)
+}
+
+/// Returns the subscription of a callback URL to `appli`.
+///
+/// # Errors
+/// - Returns `NotifyError::NoSuchSubscription` if the callback URL is not subscribed to `appli`.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/notify/operation/notify-get
+pub fn get_subscription(
+    client: &WithingsClient,
+    callbackurl: &str,
+    appli: Appli,
+) -> Result<Subscription, NotifyError> {
+    let mut params = HashMap::new();
+    params.insert("action", "get".to_string());
+    params.insert("callbackurl", callbackurl.to_string());
+    params.insert("appli", appli.to_string());
+    call(client, &params)
+}
+
+/// Moves a subscription to a new callback URL and `appli`.
+///
+/// # Errors
+/// - Returns `NotifyError::InvalidCallbackUrl` if the new URL cannot be used, without sending
+///   anything.
+/// - Returns `NotifyError::CallbackVerificationFailed` if Withings could not verify the new URL.
+/// - Returns `NotifyError::NoSuchSubscription` if the subscription does not exist.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/notify/operation/notify-update
+pub fn update_subscription(
+    client: &WithingsClient,
+    callbackurl: &str,
+    appli: Appli,
+    new_callbackurl: &str,
+    new_appli: Appli,
+    comment: Option<&str>,
+) -> Result<(), NotifyError> {
+    validate_callback_url(new_callbackurl)?;
+    let mut params = HashMap::new();
+    params.insert("action", "update".to_string());
+    params.insert("callbackurl", callbackurl.to_string());
+    params.insert("appli", appli.to_string());
+    params.insert("new_callbackurl", new_callbackurl.to_string());
+    params.insert("new_appli", new_appli.to_string());
+    if let Some(comment) = comment {
+        params.insert("comment", comment.to_string());
+    }
+    call::<serde_json::Value>(client, &params).map(|_| ())
+}
+
+/// Revokes the subscription of a callback URL to `appli`.
+///
+/// # Errors
+/// - Returns `NotifyError::NoSuchSubscription` if the subscription does not exist.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/notify/operation/notify-revoke
+pub fn revoke(client: &WithingsClient, callbackurl: &str, appli: Appli) -> Result<(), NotifyError> {
+    let mut params = HashMap::new();
+    params.insert("action", "revoke".to_string());
+    params.insert("callbackurl", callbackurl.to_string());
+    params.insert("appli", appli.to_string());
+    call::<serde_json::Value>(client, &params).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_mapped_status_has_its_error() {
+        let error = |status| NotifyError::from_status(status, Some("message".to_string()));
+        assert!(matches!(
+            error(293),
+            NotifyError::CallbackVerificationFailed(reason) if reason == "message"
+        ));
+        assert!(matches!(
+            NotifyError::from_status(293, None),
+            NotifyError::CallbackVerificationFailed(reason)
+                if reason == "the callback URL is absent or incorrect"
+        ));
+        assert!(matches!(error(294), NotifyError::NoSuchSubscription));
+        assert!(matches!(error(343), NotifyError::NoSuchSubscription));
+        assert!(matches!(error(304), NotifyError::InvalidComment));
+        assert!(matches!(error(305), NotifyError::TooManySubscriptions));
+        assert!(matches!(
+            error(2554),
+            NotifyError::Api { status: 2554, error: Some(message) } if message == "message"
+        ));
+        assert_eq!(
+            NotifyError::from_status(601, None).to_string(),
+            "API returned status 601"
+        );
+    }
+
+    #[test]
+    fn callback_urls_the_api_can_reach_are_accepted() {
+        for url in [
+            "https://example.com/withings/hook",
+            "http://example.com:8080/hook?user=1",
+            "https://93.184.216.34/hook",
+        ] {
+            assert!(validate_callback_url(url).is_ok(), "{} was rejected", url);
+        }
+    }
+
+    #[test]
+    fn callback_urls_the_api_cannot_reach_are_rejected() {
+        for url in [
+            "http://localhost/hook",
+            "https://LOCALHOST:8443/hook",
+            "http://127.0.0.1/hook",
+            "http://127.1.2.3:8080/hook",
+            "http://0.0.0.0/hook",
+            "http://[::1]/hook",
+            "http://[::]/hook",
+            "ftp://example.com/hook",
+            "file:///tmp/hook",
+            "mailto:hook@example.com",
+            "/hook",
+            "not a url",
+        ] {
+            assert!(
+                matches!(
+                    validate_callback_url(url),
+                    Err(NotifyError::InvalidCallbackUrl(_))
+                ),
+                "{} was accepted",
+                url
+            );
+        }
+    }
+}
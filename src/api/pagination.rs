@@ -0,0 +1,211 @@
+//! # pagination.rs
+//! Offset pagination shared by the v2 list endpoints. Withings answers with `more` and `offset`
+//! in the body, and the next page is requested with the same parameters plus that `offset`.
+
+use log::warn;
+use std::error::Error;
+
+/// Fetches one page, given the offset of the page or `None` for the first request. Returns the
+/// page and the offset of the next one, `None` on the last page.
+type Fetch<'a, T> = Box<dyn FnMut(Option<i64>) -> Result<(T, Option<i64>), Box<dyn Error>> + 'a>;
+
+/// Iterator over the pages of a request.
+///
+/// Iteration stops after the last page, after the first error, or when the server returns an
+/// offset that does not advance past the current one.
+pub struct Pages<'a, T> {
+    fetch: Fetch<'a, T>,
+    offset: Option<i64>,
+    pages: usize,
+    max_pages: Option<usize>,
+    done: bool,
+}
+
+impl<'a, T: 'a> Pages<'a, T> {
+    pub(crate) fn new(
+        fetch: impl FnMut(Option<i64>) -> Result<(T, Option<i64>), Box<dyn Error>> + 'a,
+    ) -> Self {
+        Pages {
+            fetch: Box::new(fetch),
+            offset: None,
+            pages: 0,
+            max_pages: None,
+            done: false,
+        }
+    }
+
+    /// Stops after `max_pages` pages even if Withings reports more.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Returns an iterator over the items of every page, using `items` to take them out of a
+    /// page.
+    pub(crate) fn items<I>(self, items: fn(T) -> Vec<I>) -> Items<'a, T, I> {
+        Items {
+            pages: self,
+            items,
+            current: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<T> Iterator for Pages<'_, T> {
+    type Item = Result<T, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.max_pages.is_some_and(|max| self.pages >= max) {
+            return None;
+        }
+
+        match (self.fetch)(self.offset) {
+            Ok((page, next)) => {
+                self.pages += 1;
+                // An offset that does not advance would request the same page forever
+                let next = next.filter(|next| {
+                    let advances = *next > self.offset.unwrap_or(0);
+                    if !advances {
+                        warn!(
+                            "Stopping pagination at offset {} that does not advance",
+                            next
+                        );
+                    }
+                    advances
+                });
+                self.offset = next;
+                self.done = next.is_none();
+                Some(Ok(page))
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Iterator over the items of every page of a request.
+pub struct Items<'a, T, I> {
+    pages: Pages<'a, T>,
+    items: fn(T) -> Vec<I>,
+    current: std::vec::IntoIter<I>,
+}
+
+impl<T, I> Items<'_, T, I> {
+    /// Stops after `max_pages` pages even if Withings reports more.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.pages.max_pages = Some(max_pages);
+        self
+    }
+}
+
+impl<T, I> Iterator for Items<'_, T, I> {
+    type Item = Result<I, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.next() {
+                return Some(Ok(item));
+            }
+            match self.pages.next()? {
+                Ok(page) => self.current = (self.items)(page).into_iter(),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Returns the offset of the next page from the `more` and `offset` fields of a body.
+pub(crate) fn next_offset(more: bool, offset: Option<i64>) -> Option<i64> {
+    offset.filter(|_| more)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    /// Serves pages of `size` numbers from `0..total`, recording the requested offsets.
+    fn numbers<'a>(
+        total: i64,
+        size: i64,
+        requested: &'a mut Vec<Option<i64>>,
+    ) -> Pages<'a, Vec<i64>> {
+        Pages::new(move |offset| {
+            requested.push(offset);
+            let start = offset.unwrap_or(0);
+            let end = total.min(start + size);
+            Ok(((start..end).collect(), next_offset(end < total, Some(end))))
+        })
+    }
+
+    #[test]
+    fn follows_the_offset_until_the_last_page() {
+        let mut requested = Vec::new();
+        let pages: Vec<_> = numbers(5, 2, &mut requested)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(pages, vec![vec![0, 1], vec![2, 3], vec![4]]);
+        assert_eq!(requested, vec![None, Some(2), Some(4)]);
+    }
+
+    #[test]
+    fn items_flattens_the_pages() {
+        let mut requested = Vec::new();
+        let items: Vec<i64> = numbers(5, 2, &mut requested)
+            .items(|page| page)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn max_pages_stops_early() {
+        let mut requested = Vec::new();
+        let items: Vec<i64> = numbers(10, 2, &mut requested)
+            .items(|page| page)
+            .max_pages(2)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(items, vec![0, 1, 2, 3]);
+        assert_eq!(requested.len(), 2);
+    }
+
+    #[test]
+    fn stops_when_the_offset_does_not_advance() {
+        let mut calls = 0;
+        let pages = Pages::new(|_| {
+            calls += 1;
+            Ok((calls, Some(3)))
+        });
+        assert_eq!(pages.take(10).count(), 2);
+
+        let pages = Pages::new(|_| Ok(((), Some(0))));
+        assert_eq!(pages.take(10).count(), 1);
+    }
+
+    #[test]
+    fn stops_after_an_error() {
+        let mut calls = 0;
+        let mut pages = Pages::new(|offset: Option<i64>| {
+            calls += 1;
+            if offset.is_some() {
+                return Err(Box::new(io::Error::other("rate limited")) as Box<dyn Error>);
+            }
+            Ok(((), Some(1)))
+        });
+        assert!(pages.next().unwrap().is_ok());
+        assert!(pages.next().unwrap().is_err());
+        assert!(pages.next().is_none());
+        drop(pages);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    fn next_offset_requires_more() {
+        assert_eq!(next_offset(true, Some(3)), Some(3));
+        assert_eq!(next_offset(false, Some(3)), None);
+        assert_eq!(next_offset(true, None), None);
+    }
+}
@@ -0,0 +1,396 @@
+//! # params.rs
+//! Typed building blocks shared by the request parameters of the data endpoints: date ranges,
+//! epoch conversion, the parameters of the v2 endpoints that take a range of days, and the
+//! validation errors raised before a request is sent.
+
+use crate::models::date::Date;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A validation error for request parameters, raised before anything is sent to the API.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamsError {
+    /// `lastupdate` cannot be combined with `startdate`/`enddate`, Withings rejects it.
+    LastUpdateWithDateRange,
+    /// The start of a date range is after its end.
+    StartAfterEnd,
+    /// A timestamp is before the Unix epoch.
+    BeforeEpoch,
+    /// A required parameter was not set.
+    Missing(&'static str),
+    /// The page offset is negative.
+    NegativeOffset(i64),
+    /// `lastupdate` was set for a call that needs every group of a window, not only the changes.
+    LastUpdateNotSupported,
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamsError::LastUpdateWithDateRange => {
+                write!(f, "lastupdate cannot be combined with startdate or enddate")
+            }
+            ParamsError::StartAfterEnd => write!(f, "the start date is after the end date"),
+            ParamsError::BeforeEpoch => write!(f, "timestamp is before the Unix epoch"),
+            ParamsError::Missing(name) => write!(f, "missing required parameter: {}", name),
+            ParamsError::NegativeOffset(offset) => write!(f, "offset {} is negative", offset),
+            ParamsError::LastUpdateNotSupported => {
+                write!(f, "lastupdate is not supported for this call")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+/// An inclusive range of time sent as `startdate`/`enddate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    start: SystemTime,
+    end: SystemTime,
+}
+
+impl DateRange {
+    /// Creates a range from `start` to `end`.
+    ///
+    /// # Errors
+    /// - Returns `ParamsError::StartAfterEnd` if `start` is after `end`.
+    /// - Returns `ParamsError::BeforeEpoch` if either bound is before the Unix epoch.
+    pub fn new(
+        start: impl Into<SystemTime>,
+        end: impl Into<SystemTime>,
+    ) -> Result<Self, ParamsError> {
+        let (start, end) = (start.into(), end.into());
+        epoch_seconds(start)?;
+        epoch_seconds(end)?;
+        if start > end {
+            return Err(ParamsError::StartAfterEnd);
+        }
+        Ok(DateRange { start, end })
+    }
+
+    /// Returns the start of the range.
+    pub fn start(&self) -> SystemTime {
+        self.start
+    }
+
+    /// Returns the end of the range.
+    pub fn end(&self) -> SystemTime {
+        self.end
+    }
+
+    /// Returns the bounds as epoch seconds.
+    pub fn to_epoch_seconds(&self) -> (i64, i64) {
+        // Both bounds were checked against the epoch in `new`.
+        (
+            epoch_seconds(self.start).unwrap_or_default(),
+            epoch_seconds(self.end).unwrap_or_default(),
+        )
+    }
+}
+
+/// Converts a timestamp to the epoch seconds expected by the API.
+///
+/// # Errors
+/// - Returns `ParamsError::BeforeEpoch` if the timestamp is before the Unix epoch.
+pub fn epoch_seconds(time: SystemTime) -> Result<i64, ParamsError> {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .map_err(|_| ParamsError::BeforeEpoch)
+}
+
+/// Splits the inclusive range of epoch seconds from `start` to `end` into consecutive inclusive
+/// windows of at most `max_window` seconds, for the endpoints that limit the range of a request.
+pub fn split_windows(start: i64, end: i64, max_window: i64) -> Vec<(i64, i64)> {
+    let mut windows = Vec::new();
+    let mut start = start;
+    loop {
+        let window_end = end.min(start + max_window.max(1) - 1);
+        windows.push((start, window_end));
+        if window_end >= end {
+            return windows;
+        }
+        start = window_end + 1;
+    }
+}
+
+/// A field that can be requested through `data_fields`, tied to the action of its endpoint.
+pub trait DataField: Copy + fmt::Debug {
+    /// The `action` of the endpoint that returns the field.
+    const ACTION: &'static str;
+
+    /// Returns the name of the field as sent in `data_fields`.
+    fn name(&self) -> &'static str;
+}
+
+/// Represents the parameters of a v2 endpoint that takes a range of days or `lastupdate`,
+/// `data_fields` and an `offset`, such as `getactivity`, `getworkouts` and `getsummary`.
+///
+/// Either a range of days or `lastupdate` must be set. The endpoint modules expose it under
+/// their own names, e.g. `activity::ActivityParams`.
+#[derive(Debug, Clone)]
+pub struct DayRangeParams<F> {
+    start: Option<Date>,
+    end: Option<Date>,
+    lastupdate: Option<i64>,
+    data_fields: Vec<F>,
+    offset: Option<i64>,
+}
+
+impl<F: DataField> DayRangeParams<F> {
+    /// Returns a builder for the parameters.
+    pub fn builder() -> DayRangeParamsBuilder<F> {
+        DayRangeParamsBuilder::default()
+    }
+
+    /// Converts the parameters into a `HashMap` of request parameters.
+    pub fn to_query_params(&self) -> HashMap<&str, String> {
+        let mut params = HashMap::new();
+
+        params.insert("action", F::ACTION.to_string());
+
+        if let Some(start) = self.start {
+            params.insert("startdateymd", start.to_string());
+        }
+        if let Some(end) = self.end {
+            params.insert("enddateymd", end.to_string());
+        }
+        if let Some(lastupdate) = self.lastupdate {
+            params.insert("lastupdate", lastupdate.to_string());
+        }
+        if !self.data_fields.is_empty() {
+            let fields: Vec<&str> = self.data_fields.iter().map(|f| f.name()).collect();
+            params.insert("data_fields", fields.join(","));
+        }
+        if let Some(offset) = self.offset {
+            params.insert("offset", offset.to_string());
+        }
+
+        params
+    }
+
+    /// Returns a copy of the parameters that requests the page at `offset`.
+    pub fn with_offset(&self, offset: i64) -> Self {
+        DayRangeParams {
+            offset: Some(offset),
+            ..self.clone()
+        }
+    }
+}
+
+/// Builder for `DayRangeParams`.
+#[derive(Debug)]
+pub struct DayRangeParamsBuilder<F> {
+    start: Option<Date>,
+    end: Option<Date>,
+    lastupdate: Option<SystemTime>,
+    data_fields: Vec<F>,
+    offset: Option<i64>,
+}
+
+impl<F> Default for DayRangeParamsBuilder<F> {
+    fn default() -> Self {
+        DayRangeParamsBuilder {
+            start: None,
+            end: None,
+            lastupdate: None,
+            data_fields: Vec::new(),
+            offset: None,
+        }
+    }
+}
+
+impl<F: DataField> DayRangeParamsBuilder<F> {
+    /// Only returns the data of `start` and later days.
+    pub fn startdate(mut self, start: Date) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Only returns the data of `end` and earlier days.
+    pub fn enddate(mut self, end: Date) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Only returns data created or modified since `lastupdate`.
+    /// Cannot be combined with `startdate`/`enddate`.
+    pub fn lastupdate(mut self, lastupdate: impl Into<SystemTime>) -> Self {
+        self.lastupdate = Some(lastupdate.into());
+        self
+    }
+
+    /// Selects the fields to return. Every field is returned by default.
+    pub fn data_fields(mut self, fields: impl IntoIterator<Item = F>) -> Self {
+        self.data_fields.extend(fields);
+        self
+    }
+
+    /// Sets the offset returned by a previous response to fetch the next page.
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Validates and builds the parameters.
+    ///
+    /// # Errors
+    /// - Returns `ParamsError::NegativeOffset` if the offset is negative.
+    /// - Returns `ParamsError::LastUpdateWithDateRange` if `lastupdate` is combined with
+    ///   `startdate` or `enddate`.
+    /// - Returns `ParamsError::Missing` if neither a range of days nor `lastupdate` is set.
+    /// - Returns `ParamsError::StartAfterEnd` if `startdate` is after `enddate`.
+    /// - Returns `ParamsError::BeforeEpoch` if `lastupdate` is before the Unix epoch.
+    pub fn build(self) -> Result<DayRangeParams<F>, ParamsError> {
+        if let Some(offset) = self.offset.filter(|offset| *offset < 0) {
+            return Err(ParamsError::NegativeOffset(offset));
+        }
+        let has_range = self.start.is_some() || self.end.is_some();
+        match (has_range, self.lastupdate.is_some()) {
+            (true, true) => return Err(ParamsError::LastUpdateWithDateRange),
+            (false, false) => return Err(ParamsError::Missing("startdateymd or lastupdate")),
+            _ => {}
+        }
+        match (self.start, self.end) {
+            (Some(start), Some(end)) if start > end => return Err(ParamsError::StartAfterEnd),
+            (Some(_), None) => return Err(ParamsError::Missing("enddateymd")),
+            (None, Some(_)) => return Err(ParamsError::Missing("startdateymd")),
+            _ => {}
+        }
+
+        Ok(DayRangeParams {
+            start: self.start,
+            end: self.end,
+            lastupdate: self.lastupdate.map(epoch_seconds).transpose()?,
+            data_fields: self.data_fields,
+            offset: self.offset,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[derive(Debug, Clone, Copy)]
+    struct Field;
+
+    impl DataField for Field {
+        const ACTION: &'static str = "getsomething";
+
+        fn name(&self) -> &'static str {
+            "field"
+        }
+    }
+
+    fn day(day: u8) -> Date {
+        Date::new(2024, 1, day).unwrap()
+    }
+
+    #[test]
+    fn date_range_rejects_start_after_end() {
+        assert_eq!(
+            DateRange::new(at(20), at(10)),
+            Err(ParamsError::StartAfterEnd)
+        );
+        let range = DateRange::new(at(10), at(10)).unwrap();
+        assert_eq!(range.to_epoch_seconds(), (10, 10));
+    }
+
+    #[test]
+    fn date_range_rejects_times_before_the_epoch() {
+        let before = UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(
+            DateRange::new(before, at(10)),
+            Err(ParamsError::BeforeEpoch)
+        );
+        assert_eq!(epoch_seconds(before), Err(ParamsError::BeforeEpoch));
+        assert_eq!(epoch_seconds(at(1706108118)), Ok(1706108118));
+    }
+
+    #[test]
+    fn errors_describe_the_parameter() {
+        assert_eq!(
+            ParamsError::Missing("startdate").to_string(),
+            "missing required parameter: startdate"
+        );
+        assert_eq!(
+            ParamsError::NegativeOffset(-3).to_string(),
+            "offset -3 is negative"
+        );
+    }
+
+    #[test]
+    fn day_range_params_build_the_query() {
+        let params = DayRangeParams::<Field>::builder()
+            .startdate(day(1))
+            .enddate(day(31))
+            .data_fields([Field, Field])
+            .build()
+            .unwrap();
+        let query = params.to_query_params();
+        assert_eq!(query["action"], "getsomething");
+        assert_eq!(query["startdateymd"], "2024-01-01");
+        assert_eq!(query["enddateymd"], "2024-01-31");
+        assert_eq!(query["data_fields"], "field,field");
+        assert!(!query.contains_key("offset"));
+
+        assert_eq!(params.with_offset(2).to_query_params()["offset"], "2");
+
+        let params = DayRangeParams::<Field>::builder()
+            .lastupdate(at(1706108118))
+            .build()
+            .unwrap();
+        assert_eq!(params.to_query_params()["lastupdate"], "1706108118");
+    }
+
+    #[test]
+    fn day_range_params_builder_validates() {
+        let builder = DayRangeParams::<Field>::builder;
+        assert_eq!(
+            builder().build().unwrap_err(),
+            ParamsError::Missing("startdateymd or lastupdate")
+        );
+        assert_eq!(
+            builder()
+                .startdate(day(1))
+                .lastupdate(at(10))
+                .build()
+                .unwrap_err(),
+            ParamsError::LastUpdateWithDateRange
+        );
+        assert_eq!(
+            builder()
+                .startdate(day(2))
+                .enddate(day(1))
+                .build()
+                .unwrap_err(),
+            ParamsError::StartAfterEnd
+        );
+        assert_eq!(
+            builder().startdate(day(1)).build().unwrap_err(),
+            ParamsError::Missing("enddateymd")
+        );
+        assert_eq!(
+            builder().enddate(day(1)).build().unwrap_err(),
+            ParamsError::Missing("startdateymd")
+        );
+        assert_eq!(
+            builder()
+                .lastupdate(UNIX_EPOCH - Duration::from_secs(1))
+                .build()
+                .unwrap_err(),
+            ParamsError::BeforeEpoch
+        );
+        assert_eq!(
+            builder().lastupdate(at(10)).offset(-1).build().unwrap_err(),
+            ParamsError::NegativeOffset(-1)
+        );
+    }
+}
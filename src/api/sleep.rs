@@ -0,0 +1,214 @@
+//! # sleep.rs
+//! Calls the Withings API to get the sleep data, which requires the `user.sleepevents` scope
+//! requested with `auth::get_access_code_with`, and the nightly sleep summaries
+//! https://developer.withings.com/api-reference/#tag/sleep/operation/sleepv2-get
+//! https://developer.withings.com/api-reference/#tag/sleep/operation/sleepv2-getsummary
+
+use crate::api::client::WithingsClient;
+use crate::api::pagination::{next_offset, Items, Pages};
+use crate::api::params::{
+    split_windows, DataField, DateRange, DayRangeParams, DayRangeParamsBuilder,
+};
+use crate::models::sleep::{
+    ResponseSleep, ResponseSleepSummary, SleepField, SleepSummary, SleepSummaryField, SleepTimeline,
+};
+use std::collections::HashMap;
+use std::error::Error;
+
+const SLEEP_V2_PATH: &str = "v2/sleep";
+/// Longest window, in seconds, Withings accepts for one sleep request
+pub const SLEEP_MAX_WINDOW: i64 = 24 * 60 * 60;
+
+/// Represents the parameters for a sleep request over a range of time.
+///
+/// The range may be longer than the 24 hours Withings accepts per request, `get_sleep_timeline`
+/// splits it into valid windows.
+#[derive(Debug, Clone)]
+pub struct SleepParams {
+    start: i64,
+    end: i64,
+    data_fields: Vec<SleepField>,
+}
+
+impl SleepParams {
+    /// Creates the parameters for a range of time, requesting the sleep states only.
+    pub fn new(range: DateRange) -> Self {
+        let (start, end) = range.to_epoch_seconds();
+        SleepParams {
+            start,
+            end,
+            data_fields: Vec::new(),
+        }
+    }
+
+    /// Selects the series to return along with the sleep states.
+    pub fn data_fields(mut self, fields: impl IntoIterator<Item = SleepField>) -> Self {
+        self.data_fields.extend(fields);
+        self
+    }
+
+    /// Splits the range into consecutive windows Withings accepts, as inclusive epoch seconds.
+    pub fn windows(&self) -> Vec<(i64, i64)> {
+        split_windows(self.start, self.end, SLEEP_MAX_WINDOW)
+    }
+
+    /// Converts the parameters of one window into a `HashMap` of request parameters.
+    pub fn to_query_params(&self, (start, end): (i64, i64)) -> HashMap<&str, String> {
+        let mut params = HashMap::new();
+
+        params.insert("action", "get".to_string());
+        params.insert("startdate", start.to_string());
+        params.insert("enddate", end.to_string());
+        if !self.data_fields.is_empty() {
+            let fields: Vec<&str> = self.data_fields.iter().map(|f| f.name()).collect();
+            params.insert("data_fields", fields.join(","));
+        }
+
+        params
+    }
+}
+
+/// Retrieves the sleep data of one window from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `params` - The series to request.
+/// * `window` - The window to request, as inclusive epoch seconds, at most 24 hours long.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::sleep::ResponseSleep` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/sleep/operation/sleepv2-get
+pub fn get_sleep(
+    client: &WithingsClient,
+    params: &SleepParams,
+    window: (i64, i64),
+) -> Result<ResponseSleep, Box<dyn Error>> {
+    client.get(SLEEP_V2_PATH, &params.to_query_params(window))
+}
+
+/// Retrieves the sleep data of the whole range of `params`, one window at a time in
+/// chronological order, merged into a single timeline.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::{Duration, SystemTime};
+/// use withings_rs::api::{params::DateRange, sleep, WithingsClient};
+/// use withings_rs::models::sleep::{SleepField, SleepState};
+///
+/// let client = WithingsClient::default();
+/// let now = SystemTime::now();
+/// let range = DateRange::new(now - Duration::from_secs(3 * 24 * 60 * 60), now).unwrap();
+/// let params = sleep::SleepParams::new(range).data_fields([SleepField::Hr, SleepField::Rr]);
+/// let timeline = sleep::get_sleep_timeline(&client, &params).unwrap();
+/// println!("{:?} of deep sleep", timeline.time_in(SleepState::Deep));
+/// ```
+///
+/// # Errors
+/// - Returns an error if a request fails. Nothing is returned for the windows fetched before.
+pub fn get_sleep_timeline(
+    client: &WithingsClient,
+    params: &SleepParams,
+) -> Result<SleepTimeline, Box<dyn Error>> {
+    let mut timeline = SleepTimeline::default();
+    for window in params.windows() {
+        timeline.extend(get_sleep(client, params, window)?.body.series);
+    }
+    Ok(timeline)
+}
+
+/// Represents the parameters for a sleep summary request.
+///
+/// Either a range of days or `lastupdate` must be set. A night belongs to the day it ends on.
+///
+/// # Examples
+///
+/// ```
+/// use withings_rs::api::sleep::SleepSummaryParams;
+/// use withings_rs::models::{date::Date, sleep::SleepSummaryField};
+///
+/// let params = SleepSummaryParams::builder()
+///     .startdate(Date::new(2024, 1, 1).unwrap())
+///     .enddate(Date::new(2024, 1, 7).unwrap())
+///     .data_fields([SleepSummaryField::SleepScore, SleepSummaryField::Wakeupcount])
+///     .build()
+///     .unwrap();
+/// assert_eq!(params.to_query_params()["data_fields"], "sleep_score,wakeupcount");
+/// ```
+pub type SleepSummaryParams = DayRangeParams<SleepSummaryField>;
+
+/// Builder for `SleepSummaryParams`.
+pub type SleepSummaryParamsBuilder = DayRangeParamsBuilder<SleepSummaryField>;
+
+impl DataField for SleepSummaryField {
+    const ACTION: &'static str = "getsummary";
+
+    fn name(&self) -> &'static str {
+        SleepSummaryField::name(self)
+    }
+}
+
+/// Retrieves one page of nightly sleep summaries from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `params` - The `SleepSummaryParams` struct containing the parameters for the API call.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::sleep::ResponseSleepSummary` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/sleep/operation/sleepv2-getsummary
+pub fn get_sleep_summary_page(
+    client: &WithingsClient,
+    params: &SleepSummaryParams,
+) -> Result<ResponseSleepSummary, Box<dyn Error>> {
+    client.get(SLEEP_V2_PATH, &params.to_query_params())
+}
+
+/// Returns an iterator over the pages of a sleep summary request, following the `offset`
+/// returned by each response.
+pub fn get_sleep_summary_pages<'a>(
+    client: &'a WithingsClient,
+    params: &SleepSummaryParams,
+) -> Pages<'a, ResponseSleepSummary> {
+    let mut params = params.clone();
+    Pages::new(move |offset| {
+        if let Some(offset) = offset {
+            params = params.with_offset(offset);
+        }
+        let response = get_sleep_summary_page(client, &params)?;
+        let next = next_offset(response.body.more, response.body.offset);
+        Ok((response, next))
+    })
+}
+
+/// Returns an iterator over every nightly sleep summary of a request, across all pages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{sleep, WithingsClient};
+///
+/// let client = WithingsClient::default();
+/// let params = sleep::SleepSummaryParams::builder()
+///     .lastupdate(std::time::UNIX_EPOCH)
+///     .build()
+///     .unwrap();
+/// for night in sleep::get_sleep_summaries(&client, &params) {
+///     let night = night.unwrap();
+///     println!("{}: score {:?}", night.date, night.data.sleep_score);
+/// }
+/// ```
+pub fn get_sleep_summaries<'a>(
+    client: &'a WithingsClient,
+    params: &SleepSummaryParams,
+) -> Items<'a, ResponseSleepSummary, SleepSummary> {
+    get_sleep_summary_pages(client, params).items(|response| response.body.series)
+}
@@ -0,0 +1,100 @@
+//! # stetho.rs
+//! Calls the Withings API to get the heart sound recordings and their signal
+//! https://developer.withings.com/api-reference/#tag/stetho
+
+use crate::api::client::WithingsClient;
+use crate::api::heart::HeartListParams;
+use crate::api::pagination::{next_offset, Items, Pages};
+use crate::models::stetho::{ResponseStethoList, ResponseStethoSignal, StethoRecording};
+use std::collections::HashMap;
+use std::error::Error;
+
+const STETHO_V2_PATH: &str = "v2/stetho";
+
+/// The stetho list takes the same date range and offset as the heart list.
+pub type StethoListParams = HeartListParams;
+
+/// Retrieves one page of heart sound recordings from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `params` - The `StethoListParams` struct containing the parameters for the API call.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::stetho::ResponseStethoList` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/stetho/operation/stethov2-list
+pub fn get_recording_page(
+    client: &WithingsClient,
+    params: &StethoListParams,
+) -> Result<ResponseStethoList, Box<dyn Error>> {
+    client.get(STETHO_V2_PATH, &params.to_query_params())
+}
+
+/// Returns an iterator over the pages of heart sound recordings, following the `offset` returned
+/// by each response.
+pub fn get_recording_pages<'a>(
+    client: &'a WithingsClient,
+    params: &StethoListParams,
+) -> Pages<'a, ResponseStethoList> {
+    let mut params = params.clone();
+    Pages::new(move |offset| {
+        if let Some(offset) = offset {
+            params = params.with_offset(offset);
+        }
+        let response = get_recording_page(client, &params)?;
+        let next = next_offset(response.body.more, response.body.offset);
+        Ok((response, next))
+    })
+}
+
+/// Returns an iterator over every heart sound recording, across all pages.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{stetho, WithingsClient};
+/// use withings_rs::models::stetho::VhdClassification;
+///
+/// let client = WithingsClient::default();
+/// let params = stetho::StethoListParams::default();
+/// for recording in stetho::get_recordings(&client, &params) {
+///     let recording = recording.unwrap();
+///     if recording.vhd == VhdClassification::Positive {
+///         let signal = stetho::get_signal(&client, recording.signalid).unwrap().body;
+///         println!("{} samples at {} Hz", signal.signal.len(), signal.frequency);
+///     }
+/// }
+/// ```
+pub fn get_recordings<'a>(
+    client: &'a WithingsClient,
+    params: &StethoListParams,
+) -> Items<'a, ResponseStethoList, StethoRecording> {
+    get_recording_pages(client, params).items(|response| response.body.series)
+}
+
+/// Retrieves the signal of a heart sound recording from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+/// * `signalid` - The `signalid` of the recording, from `get_recording_page`.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::stetho::ResponseStethoSignal` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/stetho/operation/stethov2-get
+pub fn get_signal(
+    client: &WithingsClient,
+    signalid: i64,
+) -> Result<ResponseStethoSignal, Box<dyn Error>> {
+    let mut params = HashMap::new();
+    params.insert("action", "get".to_string());
+    params.insert("signalid", signalid.to_string());
+    client.get(STETHO_V2_PATH, &params)
+}
@@ -0,0 +1,63 @@
+//! # user.rs
+//! Calls the Withings API to get the user's devices and goals
+//! https://developer.withings.com/api-reference/#tag/user
+
+use crate::api::client::WithingsClient;
+use crate::models::device::ResponseDevices;
+use crate::models::goals::ResponseGoals;
+use std::collections::HashMap;
+use std::error::Error;
+
+const USER_V2_PATH: &str = "v2/user";
+
+/// Retrieves the devices linked to the user's account from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::device::ResponseDevices` or an error.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::api::{measure, user, WithingsClient};
+/// use withings_rs::models::device::find_device;
+///
+/// let client = WithingsClient::default();
+/// let devices = user::get_devices(&client).unwrap().body.devices;
+/// let params = measure::MeasurementParams::builder().build().unwrap();
+/// for group in measure::get_measure_groups(&client, &params) {
+///     let group = group.unwrap();
+///     let battery = find_device(&devices, &group).and_then(|device| device.battery.clone());
+///     println!("{:?}, battery {:?}", group.device_name(), battery);
+/// }
+/// ```
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/user/operation/userv2-getdevice
+pub fn get_devices(client: &WithingsClient) -> Result<ResponseDevices, Box<dyn Error>> {
+    let mut params = HashMap::new();
+    params.insert("action", "getdevice".to_string());
+    client.get(USER_V2_PATH, &params)
+}
+
+/// Retrieves the goals set by the user from the Withings API.
+///
+/// # Arguments
+///
+/// * `client` - The `WithingsClient` used to authorize and send the request.
+///
+/// # Returns
+///
+/// Returns a `Result` with either `models::goals::ResponseGoals` or an error.
+///
+/// # Documentation
+/// https://developer.withings.com/api-reference/#tag/user/operation/userv2-getgoals
+pub fn get_goals(client: &WithingsClient) -> Result<ResponseGoals, Box<dyn Error>> {
+    let mut params = HashMap::new();
+    params.insert("action", "getgoals".to_string());
+    client.get(USER_V2_PATH, &params)
+}
@@ -13,63 +13,53 @@
 //!
 //! # Examples
 //!
-//!  ```no_run
-//! use withings_rs::{api::{auth, measure}, models::meas::CategoryType};
+//! ```no_run
+//! use withings_rs::api::{auth, config, measure, WithingsClient};
+//! use withings_rs::models::{meas::CategoryType, MeasureType};
 //! use std::env;
-//! use simple_logger::SimpleLogger;
 //! use std::path::Path;
-//! use withings_rs::models::MeasureType;
-//!
-//! fn main () {
-//!    println!("testing withings-rs\n");
-//!
-//!    // Initialize the logger to see the output
-//!    SimpleLogger::new().init().unwrap();
-//!   
-//!    // Get the client id from the environment variable
-//!    let client_id = env::var("WITHINGS_CLIENT_ID").unwrap();
-//!    
-//!    // Get the config file if it exists or create a new one
-//!    let config_file = auth::get_config_file();
-//!    
-//!    // Get the access token from the config file or get a new one
-//!    let access_token = get_access_token(config_file);
-//!    
-//!    // Get the CategoryType and MeasureType
-//!    let weight = MeasureType::Weight.to_string();
-//!    
-//!  // Get the CategoryType and MeasureType
-//!    let category = CategoryType::Measures.to_string();
-//!    let weight = MeasureType::Weight.to_string();
-//!    // set up the measure api arguments
-//!    let params = measure::MeasurementParams{
-//!    access_token: access_token.unwrap().to_string(),
-//!    client_id,
-//!    category,
-//!    meastype: weight,
-//!    start: None,
-//!    end: None,
-//!    offset: None,
-//!    lastupdate: Some("1706108118".to_string())
-//!    };
-//!    // Get the measurements
-//!    let measurements = measure::get_measurements(
-//!    &params
-//!    ).unwrap();
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! fn main() {
+//!     println!("testing withings-rs\n");
+//!
+//!     // Get the client id from the environment variable
+//!     let client_id = env::var("WITHINGS_CLIENT_ID").unwrap();
+//!
+//!     // Get the config file if it exists or create a new one
+//!     let config_file = config::get_config_file();
+//!
+//!     // Get the access token from the config file or get a new one
+//!     get_access_token(config_file).unwrap();
+//!
+//!     // The default client reads the access token from the config file
+//!     let client = WithingsClient::default();
+//!
+//!     // set up the measure api arguments
+//!     let params = measure::MeasurementParams::builder()
+//!         .client_id(client_id)
+//!         .category(CategoryType::Measures)
+//!         .meastype(MeasureType::Weight)
+//!         .lastupdate(UNIX_EPOCH + Duration::from_secs(1706108118))
+//!         .build()
+//!         .unwrap();
+//!
+//!     // Get the measurements
+//!     let measurements = measure::get_measurements(&client, &params).unwrap();
+//!     println!("groups: {}", measurements.body.measuregrps.len());
+//! }
 //!
 //! // Get the access token from the config file or get a new one
-//! fn get_access_token(config_file: String) -> Result<String, String>{
-//!    let client_id = env::var("WITHINGS_CLIENT_ID").unwrap();
-//!    let client_secret = env::var("WITHINGS_CLIENT_SECRET").unwrap();
-//!    
-//!    // Check if the config file exists and get the access token or get a new one
-//!    if Path::new(&config_file).exists() {
-//!        let access_token = auth::refresh_token(client_id, client_secret);
-//!        Ok(access_token)
-//!    } else {
-//!        let access_token = auth::get_access_code(client_id, client_secret);
-//!        Ok(access_token)
-//!    }
+//! fn get_access_token(config_file: String) -> Result<String, Box<dyn std::error::Error>> {
+//!     let client_id = env::var("WITHINGS_CLIENT_ID").unwrap();
+//!     let client_secret = env::var("WITHINGS_CLIENT_SECRET").unwrap();
+//!
+//!     // Check if the config file exists and get the access token or get a new one
+//!     if Path::new(&config_file).exists() {
+//!         auth::refresh_token(client_id, client_secret)
+//!     } else {
+//!         auth::get_access_code(client_id, client_secret)
+//!     }
 //! }
 //! ```
 //!
@@ -79,11 +69,29 @@
 //! from the Withings API and return the code and state. It does not have to be used, but it is included in the library.
 //! A user could use their own server to listen for the redirect. It needs to return a HashMap with the code and state.
 //!
+//! ## Testing
+//!
+//! With the `mock` cargo feature enabled, the mock module provides a local fake of the Withings API.
+//! Point a `WithingsClient` at it with the builder's `base_url` to test without the real API.
+//!
+//! ## Endpoints
+//!
+//! - `api::auth`: OAuth2 authorization code and token refresh
+//! - `api::measure`: getmeas, followed across pages, and getworkouts
+//! - `api::activity`: getactivity and getintradayactivity
+//! - `api::sleep`: sleep get and getsummary
+//! - `api::heart`: ECG recordings and their signal
+//! - `api::stetho`: heart sound recordings and their signal
+//! - `api::user`: getdevice and getgoals
+//! - `api::notify`: notification subscribe, list, get, update and revoke
+//!
 //! ## Disclaimer
 //!
 //! This library is not affiliated with Withings. Use at your own risk.
-//! This library currently only pulls in user measurements.
 
 pub mod api;
+#[cfg(feature = "mock")]
+pub mod mock;
 pub mod models;
 pub mod redirect;
+pub mod sync;
@@ -0,0 +1,324 @@
+//! # cassette
+//! Records request/response pairs from a `WithingsClient` into fixture files and replays them
+//! offline, so real payload shapes can be locked in as regression tests.
+//!
+//! In record mode a `Recorder` runs a local proxy in front of the real API. Point the client at
+//! the recorder with `WithingsClientBuilder::base_url`, run the calls, then `finish` to write the cassette. Tokens,
+//! client secrets and authorization codes are scrubbed before anything is written, and the
+//! Authorization header is never stored.
+//!
+//! In replay mode `MockServer::replay` serves the recorded responses in the order they were
+//! recorded.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use withings_rs::api::{WAPI_BASE_URL, WithingsClient};
+//! use withings_rs::mock::{cassette::{Cassette, Recorder}, MockServer};
+//!
+//! // Record once against the real API
+//! let recorder = Recorder::start(WAPI_BASE_URL, "tests/fixtures/getmeas.json").unwrap();
+//! let client = WithingsClient::builder().base_url(recorder.base_url()).build().unwrap();
+//! // ... make the calls to record ...
+//! recorder.finish().unwrap();
+//!
+//! // Replay offline
+//! let cassette = Cassette::load("tests/fixtures/getmeas.json").unwrap();
+//! let server = MockServer::replay(&cassette).unwrap();
+//! let client = WithingsClient::builder().base_url(server.base_url()).build().unwrap();
+//! ```
+
+use super::{bind, record, reply, MockResponse, MockServer, RecordedRequest};
+use crate::api::{WithingsClient, WithingsClientBuilder};
+use log::{info, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use tiny_http::Server;
+
+/// Request parameters and response fields whose values are replaced before they are recorded.
+const SECRET_FIELDS: [&str; 4] = ["access_token", "refresh_token", "client_secret", "code"];
+const REDACTED: &str = "REDACTED";
+
+/// A recorded set of interactions with the Withings API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+/// One request and the response the API gave to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: CassetteRequest,
+    pub response: CassetteResponse,
+}
+
+/// The recorded request, with query and form parameters merged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CassetteRequest {
+    pub method: String,
+    pub path: String,
+    pub params: BTreeMap<String, String>,
+}
+
+/// The recorded response. JSON bodies are stored as JSON, anything else as a string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CassetteResponse {
+    pub status: u16,
+    pub body: serde_json::Value,
+}
+
+impl Cassette {
+    /// Reads a cassette from a JSON file.
+    ///
+    /// # Errors
+    /// - Returns an error if the file cannot be read or is not a valid cassette.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        serde_json::from_reader(file).map_err(io::Error::other)
+    }
+
+    /// Writes the cassette to a JSON file.
+    ///
+    /// # Errors
+    /// - Returns an error if the file cannot be created or written.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, self).map_err(io::Error::other)
+    }
+}
+
+impl From<&CassetteResponse> for MockResponse {
+    fn from(response: &CassetteResponse) -> Self {
+        let body = match &response.body {
+            serde_json::Value::String(body) => body.clone(),
+            body => body.to_string(),
+        };
+        MockResponse::http(response.status, &body)
+    }
+}
+
+impl MockServer {
+    /// Starts a mock server that serves the responses of a cassette.
+    ///
+    /// # Errors
+    /// - Returns an error if the server cannot bind to a local port.
+    pub fn replay(cassette: &Cassette) -> io::Result<Self> {
+        let server = MockServer::start()?;
+        for interaction in &cassette.interactions {
+            let request = &interaction.request;
+            let action = request.params.get("action").cloned().unwrap_or_default();
+            server.respond(&request.path, &action, (&interaction.response).into());
+        }
+        Ok(server)
+    }
+}
+
+/// A local proxy that forwards requests to the real API and records them into a cassette.
+///
+/// The cassette is written when the recorder is finished or dropped.
+pub struct Recorder {
+    server: Arc<Server>,
+    base_url: String,
+    path: PathBuf,
+    cassette: Arc<Mutex<Cassette>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Recorder {
+    /// Starts a recording proxy in front of `upstream_url`, e.g. `api::WAPI_BASE_URL`.
+    ///
+    /// # Arguments
+    /// - `upstream_url`: The base URL requests are forwarded to.
+    /// - `path`: The file the cassette is written to.
+    ///
+    /// # Errors
+    /// - Returns an error if the proxy cannot bind to a local port.
+    pub fn start(upstream_url: &str, path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::start_with(WithingsClient::builder(), upstream_url, path)
+    }
+
+    /// Starts a recording proxy like `start`, forwarding the requests with the transport options
+    /// of `builder`, e.g. a proxy, root certificates or timeouts. The token source and base URL of
+    /// the builder are not used.
+    ///
+    /// # Errors
+    /// - Returns an error if the HTTP client cannot be built from the builder or the proxy cannot
+    ///   bind to a local port.
+    pub fn start_with(
+        mut builder: WithingsClientBuilder,
+        upstream_url: &str,
+        path: impl AsRef<Path>,
+    ) -> io::Result<Self> {
+        let client = builder
+            .build_http()
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        let (server, base_url) = bind()?;
+        let cassette = Arc::new(Mutex::new(Cassette::default()));
+        let upstream_url = upstream_url.trim_end_matches('/').to_string();
+
+        let handle = {
+            let server = Arc::clone(&server);
+            let cassette = Arc::clone(&cassette);
+            thread::spawn(move || proxy(&server, &client, &upstream_url, &cassette))
+        };
+
+        Ok(Recorder {
+            server,
+            base_url,
+            path: path.as_ref().to_path_buf(),
+            cassette,
+            handle: Some(handle),
+        })
+    }
+
+    /// Returns the base URL to hand to `WithingsClientBuilder::base_url`.
+    pub fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    /// Stops the proxy and writes the cassette.
+    ///
+    /// # Errors
+    /// - Returns an error if the cassette file cannot be written.
+    pub fn finish(mut self) -> io::Result<Cassette> {
+        self.stop();
+        let cassette = self.cassette.lock().unwrap().clone();
+        cassette.save(&self.path)?;
+        info!("Wrote cassette to {}", self.path.display());
+        Ok(cassette)
+    }
+
+    /// Stops the proxy thread. Returns `false` if it was already stopped.
+    fn stop(&mut self) -> bool {
+        match self.handle.take() {
+            Some(handle) => {
+                self.server.unblock();
+                let _ = handle.join();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if self.stop() {
+            let cassette = self.cassette.lock().unwrap();
+            if let Err(e) = cassette.save(&self.path) {
+                warn!("Could not write cassette {}: {}", self.path.display(), e);
+            }
+        }
+    }
+}
+
+/// Forwards incoming requests to the upstream API until the proxy is unblocked.
+fn proxy(
+    server: &Server,
+    client: &reqwest::blocking::Client,
+    upstream_url: &str,
+    cassette: &Mutex<Cassette>,
+) {
+    for mut req in server.incoming_requests() {
+        let recorded = match record(&mut req) {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                warn!("Recorder could not read request: {}", e);
+                reply(req, MockResponse::http(400, &e.to_string()));
+                continue;
+            }
+        };
+
+        let response = match forward(client, upstream_url, &recorded) {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Recorder could not forward request: {}", e);
+                reply(req, MockResponse::http(502, &e.to_string()));
+                continue;
+            }
+        };
+
+        let interaction = scrub(&recorded, &response);
+        trace!("Recorded interaction: {:?}", interaction);
+        cassette.lock().unwrap().interactions.push(interaction);
+        reply(req, response);
+    }
+}
+
+/// Sends a recorded request to the upstream API, keeping its authorization and body.
+fn forward(
+    client: &reqwest::blocking::Client,
+    upstream_url: &str,
+    recorded: &RecordedRequest,
+) -> Result<MockResponse, Box<dyn std::error::Error>> {
+    let method = reqwest::Method::from_bytes(recorded.method.as_bytes())?;
+    let url = format!("{}/{}", upstream_url, recorded.path);
+    let mut request = client
+        .request(method, url)
+        .query(&recorded.query)
+        .body(recorded.body.clone());
+    for name in ["Authorization", "Content-Type"] {
+        if let Some(value) = recorded.header(name) {
+            request = request.header(name, value);
+        }
+    }
+
+    let response = request.send()?;
+    let status = response.status().as_u16();
+    let body = response.text()?;
+    Ok(MockResponse { status, body })
+}
+
+/// Builds the interaction to record, with every secret replaced by `REDACTED`.
+fn scrub(request: &RecordedRequest, response: &MockResponse) -> Interaction {
+    let params = request
+        .query
+        .iter()
+        .chain(request.form.iter())
+        .map(|(key, value)| {
+            let value = if SECRET_FIELDS.contains(&key.as_str()) {
+                REDACTED.to_string()
+            } else {
+                value.clone()
+            };
+            (key.clone(), value)
+        })
+        .collect();
+
+    let mut body = serde_json::from_str(&response.body)
+        .unwrap_or_else(|_| serde_json::Value::String(response.body.clone()));
+    scrub_json(&mut body);
+
+    Interaction {
+        request: CassetteRequest {
+            method: request.method.clone(),
+            path: request.path.clone(),
+            params,
+        },
+        response: CassetteResponse {
+            status: response.status,
+            body,
+        },
+    }
+}
+
+/// Replaces the values of secret fields anywhere in a JSON document.
+fn scrub_json(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SECRET_FIELDS.contains(&key.as_str()) {
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    scrub_json(value);
+                }
+            }
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(scrub_json),
+        _ => {}
+    }
+}
@@ -0,0 +1,261 @@
+//! # Activity model
+//! Docs: https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getactivity
+//! Response bodies from the measure v2 getactivity endpoint, one summary per day, and the
+//! getintradayactivity endpoint, one sample per minute
+
+use crate::models::date::Date;
+use crate::models::de::{self, seconds};
+use crate::models::unit::{Converted, UnitPreferences};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Response from the API is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseActivity {
+    pub status: i64,
+    pub body: ActivityBody,
+}
+
+/// The body of the response is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityBody {
+    pub activities: Vec<Activity>,
+    /// Whether more summaries are available past this page
+    #[serde(default, deserialize_with = "de::bool_or_int")]
+    pub more: bool,
+    /// Offset to request the next page with, set when `more` is true
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+/// The activity summary of one day. Fields not requested with `data_fields`, or not tracked by
+/// the user's devices, are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Activity {
+    /// The day, in the timezone of the user
+    pub date: Date,
+    /// IANA timezone of the user on that day
+    pub timezone: String,
+    #[serde(default)]
+    pub deviceid: Option<String>,
+    #[serde(default)]
+    pub hash_deviceid: Option<String>,
+    /// Source of the data: 1 for Withings, 18 for Apple Health, 42 for Google Fit
+    #[serde(default)]
+    pub brand: Option<i64>,
+    /// Whether the data comes from a tracker rather than a scale
+    #[serde(default)]
+    pub is_tracker: Option<bool>,
+    #[serde(default)]
+    pub steps: Option<i64>,
+    /// Distance in meters
+    #[serde(default)]
+    pub distance: Option<f64>,
+    /// Elevation climbed in meters
+    #[serde(default)]
+    pub elevation: Option<f64>,
+    /// Seconds of soft activity
+    #[serde(default)]
+    pub soft: Option<i64>,
+    /// Seconds of moderate activity
+    #[serde(default)]
+    pub moderate: Option<i64>,
+    /// Seconds of intense activity
+    #[serde(default)]
+    pub intense: Option<i64>,
+    /// Seconds of activity, moderate and intense
+    #[serde(default)]
+    pub active: Option<i64>,
+    /// Active calories burned, in kcal
+    #[serde(default)]
+    pub calories: Option<f64>,
+    /// Total calories burned, including the basal metabolic rate, in kcal
+    #[serde(default)]
+    pub totalcalories: Option<f64>,
+    /// Average heart rate in beats per minute
+    #[serde(default)]
+    pub hr_average: Option<i64>,
+    #[serde(default)]
+    pub hr_min: Option<i64>,
+    #[serde(default)]
+    pub hr_max: Option<i64>,
+    /// Seconds in the light heart rate zone
+    #[serde(default)]
+    pub hr_zone_0: Option<i64>,
+    /// Seconds in the moderate heart rate zone
+    #[serde(default)]
+    pub hr_zone_1: Option<i64>,
+    /// Seconds in the intense heart rate zone
+    #[serde(default)]
+    pub hr_zone_2: Option<i64>,
+    /// Seconds in the maximal heart rate zone
+    #[serde(default)]
+    pub hr_zone_3: Option<i64>,
+}
+
+impl Activity {
+    /// Returns the time spent in soft activity.
+    pub fn soft_duration(&self) -> Option<Duration> {
+        seconds(self.soft)
+    }
+
+    /// Returns the time spent in moderate activity.
+    pub fn moderate_duration(&self) -> Option<Duration> {
+        seconds(self.moderate)
+    }
+
+    /// Returns the time spent in intense activity.
+    pub fn intense_duration(&self) -> Option<Duration> {
+        seconds(self.intense)
+    }
+
+    /// Returns the time spent active.
+    pub fn active_duration(&self) -> Option<Duration> {
+        seconds(self.active)
+    }
+
+    /// Returns the time spent in each heart rate zone, from light to maximal.
+    pub fn hr_zones(&self) -> [Option<Duration>; 4] {
+        [
+            seconds(self.hr_zone_0),
+            seconds(self.hr_zone_1),
+            seconds(self.hr_zone_2),
+            seconds(self.hr_zone_3),
+        ]
+    }
+
+    /// Returns the distance in the unit chosen by `preferences`.
+    pub fn distance_in(&self, preferences: &UnitPreferences) -> Option<Converted> {
+        self.distance
+            .map(|meters| preferences.convert_distance(meters))
+    }
+}
+
+/// The fields of a daily summary that can be requested with `data_fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActivityField {
+    Steps,
+    Distance,
+    Elevation,
+    Soft,
+    Moderate,
+    Intense,
+    Active,
+    Calories,
+    TotalCalories,
+    HrAverage,
+    HrMin,
+    HrMax,
+    HrZone0,
+    HrZone1,
+    HrZone2,
+    HrZone3,
+}
+
+impl ActivityField {
+    /// Returns the name of the field in the API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActivityField::Steps => "steps",
+            ActivityField::Distance => "distance",
+            ActivityField::Elevation => "elevation",
+            ActivityField::Soft => "soft",
+            ActivityField::Moderate => "moderate",
+            ActivityField::Intense => "intense",
+            ActivityField::Active => "active",
+            ActivityField::Calories => "calories",
+            ActivityField::TotalCalories => "totalcalories",
+            ActivityField::HrAverage => "hr_average",
+            ActivityField::HrMin => "hr_min",
+            ActivityField::HrMax => "hr_max",
+            ActivityField::HrZone0 => "hr_zone_0",
+            ActivityField::HrZone1 => "hr_zone_1",
+            ActivityField::HrZone2 => "hr_zone_2",
+            ActivityField::HrZone3 => "hr_zone_3",
+        }
+    }
+}
+
+/// Response from the measure v2 getintradayactivity endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseIntraday {
+    pub status: i64,
+    pub body: IntradayBody,
+}
+
+/// The body of an intraday response: the samples keyed by epoch seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntradayBody {
+    #[serde(default, deserialize_with = "de::timestamp_map")]
+    pub series: BTreeMap<i64, IntradaySample>,
+}
+
+/// The data recorded by a device at one point in time, usually one minute. Fields the device did
+/// not record, or not requested with `data_fields`, are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntradaySample {
+    #[serde(default)]
+    pub deviceid: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub model_id: Option<i64>,
+    #[serde(default)]
+    pub steps: Option<i64>,
+    /// Elevation climbed in meters
+    #[serde(default)]
+    pub elevation: Option<f64>,
+    /// Active calories burned, in kcal
+    #[serde(default)]
+    pub calories: Option<f64>,
+    /// Distance in meters
+    #[serde(default)]
+    pub distance: Option<f64>,
+    /// Swim strokes
+    #[serde(default)]
+    pub stroke: Option<i64>,
+    /// Pool lengths
+    #[serde(default)]
+    pub pool_lap: Option<i64>,
+    /// Seconds covered by the sample
+    #[serde(default)]
+    pub duration: Option<i64>,
+    /// Heart rate in beats per minute
+    #[serde(default)]
+    pub heart_rate: Option<i64>,
+    /// Blood oxygen saturation in percent, measured automatically
+    #[serde(default)]
+    pub spo2_auto: Option<f64>,
+}
+
+/// The fields of an intraday sample that can be requested with `data_fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntradayField {
+    Steps,
+    Elevation,
+    Calories,
+    Distance,
+    Stroke,
+    PoolLap,
+    Duration,
+    HeartRate,
+    Spo2Auto,
+}
+
+impl IntradayField {
+    /// Returns the name of the field in the API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntradayField::Steps => "steps",
+            IntradayField::Elevation => "elevation",
+            IntradayField::Calories => "calories",
+            IntradayField::Distance => "distance",
+            IntradayField::Stroke => "stroke",
+            IntradayField::PoolLap => "pool_lap",
+            IntradayField::Duration => "duration",
+            IntradayField::HeartRate => "heart_rate",
+            IntradayField::Spo2Auto => "spo2_auto",
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! # Calendar dates
+//! The v2 endpoints exchange days as `YYYY-MM-DD` strings, e.g. the `date` of a daily activity
+//! summary or the `startdateymd`/`enddateymd` request parameters. `Date` is a plain calendar day
+//! with no timezone, the day as seen by the user.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A calendar day in the proleptic Gregorian calendar.
+///
+/// # Examples
+///
+/// ```
+/// use withings_rs::models::date::Date;
+///
+/// let date: Date = "2024-02-29".parse().unwrap();
+/// assert_eq!(date, Date::new(2024, 2, 29).unwrap());
+/// assert_eq!(date.to_string(), "2024-02-29");
+/// assert!("2023-02-29".parse::<Date>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+/// Error returned for a day that does not exist or a string that is not `YYYY-MM-DD`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidDate(pub String);
+
+impl fmt::Display for InvalidDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid date: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidDate {}
+
+impl Date {
+    /// Creates a date, `None` if the day does not exist.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let valid = (1..=12).contains(&month) && day >= 1 && day <= days_in_month(year, month);
+        valid.then_some(Date { year, month, day })
+    }
+
+    /// Returns the UTC day of epoch seconds.
+    pub fn from_epoch(epoch: i64) -> Self {
+        Self::from_days(epoch.div_euclid(86_400))
+    }
+
+    /// Returns the epoch seconds of midnight UTC at the start of the day.
+    pub fn to_epoch(&self) -> i64 {
+        self.to_days() * 86_400
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns the day after this one.
+    pub fn next_day(&self) -> Self {
+        Self::from_days(self.to_days() + 1)
+    }
+
+    /// Days since 1970-01-01, from Howard Hinnant's `days_from_civil`.
+    fn to_days(self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = i64::from(self.month);
+        let doy =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The date `days` after 1970-01-01, from Howard Hinnant's `civil_from_days`.
+    fn from_days(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let doe = days - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Date { year, month, day }
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Formats the date as `YYYY-MM-DD`.
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date.
+impl FromStr for Date {
+    type Err = InvalidDate;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidDate(s.to_string());
+        let mut parts = s.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let year = next()?.parse().map_err(|_| invalid())?;
+        let month = next()?.parse().map_err(|_| invalid())?;
+        let day = next()?.parse().map_err(|_| invalid())?;
+        Date::new(year, month, day).ok_or_else(invalid)
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Converts to a `chrono` date, failing for years outside the range `chrono` supports.
+#[cfg(feature = "chrono")]
+impl TryFrom<Date> for ::chrono::NaiveDate {
+    type Error = InvalidDate;
+
+    fn try_from(date: Date) -> Result<Self, Self::Error> {
+        ::chrono::NaiveDate::from_ymd_opt(date.year, date.month.into(), date.day.into())
+            .ok_or_else(|| InvalidDate(date.to_string()))
+    }
+}
+
+/// Converts to a `time` date, failing for years outside the range `time` supports.
+#[cfg(feature = "time")]
+impl TryFrom<Date> for ::time::Date {
+    type Error = InvalidDate;
+
+    fn try_from(date: Date) -> Result<Self, Self::Error> {
+        let invalid = |_| InvalidDate(date.to_string());
+        let month = ::time::Month::try_from(date.month).map_err(invalid)?;
+        ::time::Date::from_calendar_date(date.year, month, date.day).map_err(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_rejects_days_that_do_not_exist() {
+        assert!(Date::new(2024, 2, 29).is_some());
+        assert!(Date::new(2023, 2, 29).is_none());
+        assert!(Date::new(2000, 2, 29).is_some());
+        assert!(Date::new(1900, 2, 29).is_none());
+        assert!(Date::new(2024, 4, 31).is_none());
+        assert!(Date::new(2024, 13, 1).is_none());
+        assert!(Date::new(2024, 1, 0).is_none());
+    }
+
+    #[test]
+    fn epoch_round_trips() {
+        assert_eq!(Date::from_epoch(0), Date::new(1970, 1, 1).unwrap());
+        assert_eq!(Date::from_epoch(-1), Date::new(1969, 12, 31).unwrap());
+        assert_eq!(
+            Date::from_epoch(1706108118),
+            Date::new(2024, 1, 24).unwrap()
+        );
+        assert_eq!(Date::new(2024, 1, 24).unwrap().to_epoch(), 1706054400);
+        for days in [-800_000, -1, 0, 59, 60, 11_016, 800_000] {
+            let date = Date::from_epoch(days * 86_400);
+            assert_eq!(date.to_epoch(), days * 86_400);
+        }
+    }
+
+    #[test]
+    fn next_day_rolls_over_months_and_years() {
+        let next = |y, m, d| Date::new(y, m, d).unwrap().next_day();
+        assert_eq!(next(2024, 2, 28), Date::new(2024, 2, 29).unwrap());
+        assert_eq!(next(2023, 2, 28), Date::new(2023, 3, 1).unwrap());
+        assert_eq!(next(2023, 12, 31), Date::new(2024, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn parses_and_formats_ymd() {
+        let date: Date = "2024-03-05".parse().unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2024, 3, 5));
+        assert_eq!(date.to_string(), "2024-03-05");
+        assert_eq!(Date::new(987, 1, 2).unwrap().to_string(), "0987-01-02");
+
+        for invalid in [
+            "",
+            "2024",
+            "2024-03",
+            "2024-02-30",
+            "2024-03-xx",
+            "24-3-5-1",
+        ] {
+            assert_eq!(
+                invalid.parse::<Date>(),
+                Err(InvalidDate(invalid.to_string()))
+            );
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn converts_to_chrono() {
+        let date = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(
+            ::chrono::NaiveDate::try_from(date),
+            Ok(::chrono::NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())
+        );
+        assert!(::chrono::NaiveDate::try_from(Date::new(i32::MAX, 1, 1).unwrap()).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn converts_to_time() {
+        let date = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(
+            ::time::Date::try_from(date),
+            Ok(::time::Date::from_calendar_date(2024, ::time::Month::February, 29).unwrap())
+        );
+        assert!(::time::Date::try_from(Date::new(i32::MAX, 1, 1).unwrap()).is_err());
+    }
+
+    #[test]
+    fn serializes_as_a_string() {
+        let date: Date = serde_json::from_str("\"2024-01-24\"").unwrap();
+        assert_eq!(date, Date::new(2024, 1, 24).unwrap());
+        assert_eq!(serde_json::to_string(&date).unwrap(), "\"2024-01-24\"");
+        assert!(serde_json::from_str::<Date>("20240124").is_err());
+    }
+}
@@ -0,0 +1,467 @@
+//! # Date-time accessors
+//! The models keep the epoch seconds and IANA timezone names sent by Withings. With the `chrono`
+//! or `time` cargo feature enabled, the submodule of the same name converts them to proper
+//! date-time types, localized in the user's timezone so a late night weigh-in lands on the right
+//! day.
+//!
+//! The models with a start, an end or a single timestamp implement `Timed`, which both submodules
+//! build their `TimedExt` accessors on. Every accessor returns `None` instead of guessing when a
+//! timezone name is not known or an epoch is out of the range of the date-time type.
+
+use crate::models::activity::Activity;
+use crate::models::device::Device;
+use crate::models::heart::HeartRecording;
+use crate::models::sleep::{SleepSegment, SleepSummary};
+use crate::models::stetho::StethoRecording;
+use crate::models::workout::Workout;
+
+/// A model with a start, an optional end and the IANA timezone it was recorded in.
+pub trait Timed {
+    /// Returns the start as epoch seconds, or the time of a model with a single timestamp.
+    fn start_epoch(&self) -> Option<i64>;
+    /// Returns the end as epoch seconds, `None` for a model with a single timestamp.
+    fn end_epoch(&self) -> Option<i64> {
+        None
+    }
+    /// Returns the IANA timezone name sent with the model, e.g. `Europe/Paris`.
+    fn timezone_name(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl Timed for Workout {
+    fn start_epoch(&self) -> Option<i64> {
+        Some(self.startdate)
+    }
+
+    fn end_epoch(&self) -> Option<i64> {
+        Some(self.enddate)
+    }
+
+    fn timezone_name(&self) -> Option<&str> {
+        Some(&self.timezone)
+    }
+}
+
+impl Timed for SleepSummary {
+    fn start_epoch(&self) -> Option<i64> {
+        Some(self.startdate)
+    }
+
+    fn end_epoch(&self) -> Option<i64> {
+        Some(self.enddate)
+    }
+
+    fn timezone_name(&self) -> Option<&str> {
+        Some(&self.timezone)
+    }
+}
+
+/// Segments are sent without a timezone, localize them with the timezone of the summary.
+impl Timed for SleepSegment {
+    fn start_epoch(&self) -> Option<i64> {
+        Some(self.startdate)
+    }
+
+    fn end_epoch(&self) -> Option<i64> {
+        Some(self.enddate)
+    }
+}
+
+impl Timed for HeartRecording {
+    fn start_epoch(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+
+    fn timezone_name(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+}
+
+impl Timed for StethoRecording {
+    fn start_epoch(&self) -> Option<i64> {
+        Some(self.timestamp)
+    }
+
+    fn timezone_name(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+}
+
+/// The time of a device is its last sync, `None` if it never synced.
+impl Timed for Device {
+    fn start_epoch(&self) -> Option<i64> {
+        self.last_session_date
+    }
+
+    fn timezone_name(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+}
+
+/// A daily summary has a calendar `date` rather than a timestamp, only its timezone is exposed.
+impl Timed for Activity {
+    fn start_epoch(&self) -> Option<i64> {
+        None
+    }
+
+    fn timezone_name(&self) -> Option<&str> {
+        Some(&self.timezone)
+    }
+}
+
+/// Date-time accessors using `chrono` and `chrono-tz`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::models::datetime::chrono::{BodyExt, MeasuregrpExt, TimedExt};
+/// # fn report(body: &withings_rs::models::Body, workout: &withings_rs::models::workout::Workout) {
+/// for group in &body.measuregrps {
+///     if let Some(date) = body.local_date(group) {
+///         println!("{} at {}", group.grpid(), date);
+///     }
+/// }
+/// println!("workout started {:?}", workout.start_local());
+/// # }
+/// ```
+#[cfg(feature = "chrono")]
+pub mod chrono {
+    use super::Timed;
+    use crate::models::meas::{Body, Measuregrp};
+    use ::chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    /// Parses an IANA timezone name as sent by Withings, `None` if it is not known.
+    pub fn parse_timezone(name: &str) -> Option<Tz> {
+        name.parse().ok()
+    }
+
+    /// Converts epoch seconds to a UTC date-time, `None` if they are out of range.
+    pub fn utc(epoch: i64) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(epoch, 0)
+    }
+
+    /// Converts epoch seconds to a date-time in an IANA timezone, `None` if the timezone is not
+    /// known or the epoch is out of range.
+    pub fn localize(epoch: i64, timezone: &str) -> Option<DateTime<Tz>> {
+        let tz = parse_timezone(timezone)?;
+        Some(utc(epoch)?.with_timezone(&tz))
+    }
+
+    /// Date-time accessors for `Body`.
+    pub trait BodyExt {
+        /// Returns the time of the response.
+        fn updatetime_utc(&self) -> Option<DateTime<Utc>>;
+        /// Returns the timezone of the user, `None` if it is not known.
+        fn tz(&self) -> Option<Tz>;
+        /// Returns when the measures of a group were taken, in the timezone of the user.
+        fn local_date(&self, group: &Measuregrp) -> Option<DateTime<Tz>>;
+    }
+
+    impl BodyExt for Body {
+        fn updatetime_utc(&self) -> Option<DateTime<Utc>> {
+            utc(self.updatetime())
+        }
+
+        fn tz(&self) -> Option<Tz> {
+            parse_timezone(self.timezone())
+        }
+
+        fn local_date(&self, group: &Measuregrp) -> Option<DateTime<Tz>> {
+            localize(group.date(), self.timezone())
+        }
+    }
+
+    /// Date-time accessors for `Measuregrp`.
+    pub trait MeasuregrpExt {
+        /// Returns when the measures were taken.
+        fn date_utc(&self) -> Option<DateTime<Utc>>;
+        /// Returns when the measure group was stored.
+        fn created_utc(&self) -> Option<DateTime<Utc>>;
+        /// Returns when the measure group was last modified.
+        fn modified_utc(&self) -> Option<DateTime<Utc>>;
+        /// Returns when the measures were taken, in the given timezone.
+        fn date_in(&self, tz: Tz) -> Option<DateTime<Tz>>;
+    }
+
+    impl MeasuregrpExt for Measuregrp {
+        fn date_utc(&self) -> Option<DateTime<Utc>> {
+            utc(self.date())
+        }
+
+        fn created_utc(&self) -> Option<DateTime<Utc>> {
+            utc(self.created())
+        }
+
+        fn modified_utc(&self) -> Option<DateTime<Utc>> {
+            utc(self.modified())
+        }
+
+        fn date_in(&self, tz: Tz) -> Option<DateTime<Tz>> {
+            Some(self.date_utc()?.with_timezone(&tz))
+        }
+    }
+
+    /// Date-time accessors for the models implementing `Timed`: workouts, sleep summaries and
+    /// segments, heart and stetho recordings, devices and daily activity summaries.
+    pub trait TimedExt {
+        /// Returns the timezone of the model, `None` if it has none or it is not known.
+        fn tz(&self) -> Option<Tz>;
+        /// Returns the start, or the time of a model with a single timestamp.
+        fn start_utc(&self) -> Option<DateTime<Utc>>;
+        /// Returns the end.
+        fn end_utc(&self) -> Option<DateTime<Utc>>;
+        /// Returns the start in the timezone of the model.
+        fn start_local(&self) -> Option<DateTime<Tz>>;
+        /// Returns the end in the timezone of the model.
+        fn end_local(&self) -> Option<DateTime<Tz>>;
+    }
+
+    impl<T: Timed> TimedExt for T {
+        fn tz(&self) -> Option<Tz> {
+            parse_timezone(self.timezone_name()?)
+        }
+
+        fn start_utc(&self) -> Option<DateTime<Utc>> {
+            utc(self.start_epoch()?)
+        }
+
+        fn end_utc(&self) -> Option<DateTime<Utc>> {
+            utc(self.end_epoch()?)
+        }
+
+        fn start_local(&self) -> Option<DateTime<Tz>> {
+            Some(self.start_utc()?.with_timezone(&self.tz()?))
+        }
+
+        fn end_local(&self) -> Option<DateTime<Tz>> {
+            Some(self.end_utc()?.with_timezone(&self.tz()?))
+        }
+    }
+}
+
+/// Date-time accessors using `time` and `time-tz`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use withings_rs::models::datetime::time::{BodyExt, MeasuregrpExt, TimedExt};
+/// # fn report(body: &withings_rs::models::Body, workout: &withings_rs::models::workout::Workout) {
+/// for group in &body.measuregrps {
+///     if let Some(date) = body.local_date(group) {
+///         println!("{} on {}", group.grpid(), date.date());
+///     }
+/// }
+/// println!("workout started {:?}", workout.start_local());
+/// # }
+/// ```
+#[cfg(feature = "time")]
+pub mod time {
+    use super::Timed;
+    use crate::models::meas::{Body, Measuregrp};
+    use ::time::OffsetDateTime;
+    use time_tz::{timezones, OffsetDateTimeExt, Tz};
+
+    /// Looks up an IANA timezone name as sent by Withings, `None` if it is not known.
+    pub fn parse_timezone(name: &str) -> Option<&'static Tz> {
+        timezones::get_by_name(name)
+    }
+
+    /// Converts epoch seconds to a UTC date-time, `None` if they are out of range.
+    pub fn utc(epoch: i64) -> Option<OffsetDateTime> {
+        OffsetDateTime::from_unix_timestamp(epoch).ok()
+    }
+
+    /// Converts epoch seconds to a date-time in an IANA timezone, `None` if the timezone is not
+    /// known or the epoch is out of range.
+    pub fn localize(epoch: i64, timezone: &str) -> Option<OffsetDateTime> {
+        let tz = parse_timezone(timezone)?;
+        Some(utc(epoch)?.to_timezone(tz))
+    }
+
+    /// Date-time accessors for `Body`.
+    pub trait BodyExt {
+        /// Returns the time of the response.
+        fn updatetime_utc(&self) -> Option<OffsetDateTime>;
+        /// Returns the timezone of the user, `None` if it is not known.
+        fn tz(&self) -> Option<&'static Tz>;
+        /// Returns when the measures of a group were taken, in the timezone of the user.
+        fn local_date(&self, group: &Measuregrp) -> Option<OffsetDateTime>;
+    }
+
+    impl BodyExt for Body {
+        fn updatetime_utc(&self) -> Option<OffsetDateTime> {
+            utc(self.updatetime())
+        }
+
+        fn tz(&self) -> Option<&'static Tz> {
+            parse_timezone(self.timezone())
+        }
+
+        fn local_date(&self, group: &Measuregrp) -> Option<OffsetDateTime> {
+            localize(group.date(), self.timezone())
+        }
+    }
+
+    /// Date-time accessors for `Measuregrp`.
+    pub trait MeasuregrpExt {
+        /// Returns when the measures were taken.
+        fn date_utc(&self) -> Option<OffsetDateTime>;
+        /// Returns when the measure group was stored.
+        fn created_utc(&self) -> Option<OffsetDateTime>;
+        /// Returns when the measure group was last modified.
+        fn modified_utc(&self) -> Option<OffsetDateTime>;
+        /// Returns when the measures were taken, in the given timezone.
+        fn date_in(&self, tz: &Tz) -> Option<OffsetDateTime>;
+    }
+
+    impl MeasuregrpExt for Measuregrp {
+        fn date_utc(&self) -> Option<OffsetDateTime> {
+            utc(self.date())
+        }
+
+        fn created_utc(&self) -> Option<OffsetDateTime> {
+            utc(self.created())
+        }
+
+        fn modified_utc(&self) -> Option<OffsetDateTime> {
+            utc(self.modified())
+        }
+
+        fn date_in(&self, tz: &Tz) -> Option<OffsetDateTime> {
+            Some(self.date_utc()?.to_timezone(tz))
+        }
+    }
+
+    /// Date-time accessors for the models implementing `Timed`: workouts, sleep summaries and
+    /// segments, heart and stetho recordings, devices and daily activity summaries.
+    pub trait TimedExt {
+        /// Returns the timezone of the model, `None` if it has none or it is not known.
+        fn tz(&self) -> Option<&'static Tz>;
+        /// Returns the start, or the time of a model with a single timestamp.
+        fn start_utc(&self) -> Option<OffsetDateTime>;
+        /// Returns the end.
+        fn end_utc(&self) -> Option<OffsetDateTime>;
+        /// Returns the start in the timezone of the model.
+        fn start_local(&self) -> Option<OffsetDateTime>;
+        /// Returns the end in the timezone of the model.
+        fn end_local(&self) -> Option<OffsetDateTime>;
+    }
+
+    impl<T: Timed> TimedExt for T {
+        fn tz(&self) -> Option<&'static Tz> {
+            parse_timezone(self.timezone_name()?)
+        }
+
+        fn start_utc(&self) -> Option<OffsetDateTime> {
+            utc(self.start_epoch()?)
+        }
+
+        fn end_utc(&self) -> Option<OffsetDateTime> {
+            utc(self.end_epoch()?)
+        }
+
+        fn start_local(&self) -> Option<OffsetDateTime> {
+            Some(self.start_utc()?.to_timezone(self.tz()?))
+        }
+
+        fn end_local(&self) -> Option<OffsetDateTime> {
+            Some(self.end_utc()?.to_timezone(self.tz()?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workout(timezone: &str, startdate: i64) -> Workout {
+        serde_json::from_value(serde_json::json!({
+            "category": 1,
+            "timezone": timezone,
+            "attrib": 0,
+            "startdate": startdate,
+            "enddate": startdate + 3600,
+            "date": "2024-01-24"
+        }))
+        .unwrap()
+    }
+
+    fn device(last_session_date: Option<i64>) -> Device {
+        serde_json::from_value(serde_json::json!({
+            "type": "Scale",
+            "model": "Body+",
+            "model_id": 13,
+            "deviceid": "abc",
+            "timezone": "America/New_York",
+            "last_session_date": last_session_date
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn timed_exposes_the_raw_fields() {
+        let workout = workout("Europe/Paris", 1706083263);
+        assert_eq!(workout.start_epoch(), Some(1706083263));
+        assert_eq!(workout.end_epoch(), Some(1706086863));
+        assert_eq!(workout.timezone_name(), Some("Europe/Paris"));
+        assert_eq!(device(None).start_epoch(), None);
+        assert_eq!(device(None).end_epoch(), None);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_localizes_in_the_model_timezone() {
+        use super::chrono::TimedExt;
+        use ::chrono::{Datelike, Timelike};
+
+        // 23:30 UTC on Jan 23 is already Jan 24 in Paris
+        let workout = workout("Europe/Paris", 1706052600);
+        let local = workout.start_local().unwrap();
+        assert_eq!((local.day(), local.hour()), (24, 0));
+        assert_eq!(workout.start_utc().unwrap().day(), 23);
+        assert_eq!(workout.end_local().unwrap().hour(), 1);
+
+        assert!(device(Some(1706052600)).start_local().is_some());
+        assert!(device(None).start_utc().is_none());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_does_not_guess_unknown_values() {
+        use super::chrono::{localize, utc, TimedExt};
+
+        let workout = workout("Mars/Olympus_Mons", 1706052600);
+        assert!(workout.tz().is_none());
+        assert!(workout.start_local().is_none());
+        assert!(workout.start_utc().is_some());
+
+        assert!(utc(i64::MAX).is_none());
+        assert!(localize(i64::MAX, "Europe/Paris").is_none());
+        assert!(localize(0, "Mars/Olympus_Mons").is_none());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_localizes_in_the_model_timezone() {
+        use super::time::TimedExt;
+
+        let workout = workout("Europe/Paris", 1706052600);
+        let local = workout.start_local().unwrap();
+        assert_eq!((local.day(), local.hour()), (24, 0));
+        assert_eq!(workout.start_utc().unwrap().day(), 23);
+        assert!(device(None).start_utc().is_none());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_does_not_guess_unknown_values() {
+        use super::time::{localize, utc, TimedExt};
+
+        assert!(workout("Mars/Olympus_Mons", 0).start_local().is_none());
+        assert!(utc(i64::MAX).is_none());
+        assert!(localize(i64::MAX, "Europe/Paris").is_none());
+        assert!(localize(0, "Mars/Olympus_Mons").is_none());
+    }
+}
@@ -0,0 +1,81 @@
+//! # Deserialize helpers
+//! Withings is not consistent about the JSON types of some fields across endpoints. These helpers
+//! accept every shape the API is known to send, and decode the raw values shared by the models.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Deserializes a flag sent either as a bool or as a 0/1 integer, e.g. the `more` field.
+pub(crate) fn bool_or_int<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrInt {
+        Bool(bool),
+        Int(i64),
+    }
+
+    Ok(match BoolOrInt::deserialize(deserializer)? {
+        BoolOrInt::Bool(value) => value,
+        BoolOrInt::Int(value) => value != 0,
+    })
+}
+
+/// Deserializes a map keyed by epoch seconds, which Withings sends as an empty array when there
+/// is no data, e.g. the `series` of an intraday activity response.
+pub(crate) fn timestamp_map<'de, D, V>(deserializer: D) -> Result<BTreeMap<i64, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MapOrSeq<V> {
+        Map(BTreeMap<String, V>),
+        // Only there to accept the empty array
+        #[allow(dead_code)]
+        Seq(Vec<serde::de::IgnoredAny>),
+    }
+
+    match MapOrSeq::deserialize(deserializer)? {
+        MapOrSeq::Map(map) => map
+            .into_iter()
+            .map(|(key, value)| {
+                key.parse()
+                    .map(|timestamp| (timestamp, value))
+                    .map_err(|_| D::Error::custom(format!("invalid timestamp: {}", key)))
+            })
+            .collect(),
+        MapOrSeq::Seq(_) => Ok(BTreeMap::new()),
+    }
+}
+
+/// Converts a number of seconds sent by the API, ignoring negative values.
+pub(crate) fn seconds(seconds: Option<i64>) -> Option<Duration> {
+    seconds
+        .and_then(|s| u64::try_from(s).ok())
+        .map(Duration::from_secs)
+}
+
+/// Returns the samples of a raw signal with their offset from the start of the recording. A
+/// frequency below 1 Hz is read as 1 Hz.
+pub(crate) fn signal_samples(
+    signal: &[i64],
+    frequency: i64,
+) -> impl Iterator<Item = (Duration, f64)> + '_ {
+    let frequency = frequency.max(1) as f64;
+    signal
+        .iter()
+        .enumerate()
+        .map(move |(i, &sample)| (Duration::from_secs_f64(i as f64 / frequency), sample as f64))
+}
+
+/// Returns the length of a raw signal sampled at `frequency`. A frequency below 1 Hz is read as
+/// 1 Hz.
+pub(crate) fn signal_duration(signal: &[i64], frequency: i64) -> Duration {
+    Duration::from_secs_f64(signal.len() as f64 / frequency.max(1) as f64)
+}
@@ -0,0 +1,336 @@
+//! # Device model
+//! Docs: https://developer.withings.com/api-reference/#tag/user/operation/userv2-getdevice
+//! Response body from the user v2 getdevice endpoint, and a catalogue of the Withings model ids
+//! to name the device of a measure group.
+
+use crate::models::meas::Measuregrp;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Response from the API is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseDevices {
+    pub status: i64,
+    pub body: DevicesBody,
+}
+
+/// The body of the response is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevicesBody {
+    pub devices: Vec<Device>,
+}
+
+/// A device linked to the user's account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Device {
+    #[serde(rename = "type")]
+    pub device_type: DeviceType,
+    /// Name of the model, e.g. `Body Cardio`
+    pub model: String,
+    pub model_id: i64,
+    #[serde(default)]
+    pub battery: Option<BatteryLevel>,
+    pub deviceid: String,
+    #[serde(default)]
+    pub hash_deviceid: Option<String>,
+    /// IANA timezone of the device
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Last synchronization, as epoch seconds
+    #[serde(default)]
+    pub last_session_date: Option<i64>,
+    #[serde(default)]
+    pub mac_address: Option<String>,
+    /// Firmware version
+    #[serde(default)]
+    pub fw: Option<String>,
+}
+
+impl Device {
+    /// Returns `true` if the measure group was captured by this device.
+    pub fn captured(&self, group: &Measuregrp) -> bool {
+        match (group.hash_deviceid(), self.hash_deviceid.as_deref()) {
+            (Some(group_hash), Some(hash)) => group_hash == hash,
+            _ => group.deviceid() == Some(self.deviceid.as_str()),
+        }
+    }
+}
+
+/// Finds the device that captured a measure group among the user's devices.
+pub fn find_device<'a>(devices: &'a [Device], group: &Measuregrp) -> Option<&'a Device> {
+    devices.iter().find(|device| device.captured(group))
+}
+
+/// The kind of device, from the `type` field
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceType {
+    Scale,
+    ActivityTracker,
+    BloodPressureMonitor,
+    Thermometer,
+    SleepMonitor,
+    Babyphone,
+    /// A `type` value this library does not know
+    Unknown(String),
+}
+
+impl DeviceType {
+    /// Returns the `type` value used by the API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            DeviceType::Scale => "Scale",
+            DeviceType::ActivityTracker => "Activity Tracker",
+            DeviceType::BloodPressureMonitor => "Blood Pressure Monitor",
+            DeviceType::Thermometer => "Smart Connected Thermometer",
+            DeviceType::SleepMonitor => "Sleep Monitor",
+            DeviceType::Babyphone => "babyphone",
+            DeviceType::Unknown(name) => name,
+        }
+    }
+
+    /// Returns the `DeviceType` for a `type` value, `Unknown` if the value is not known.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Scale" => DeviceType::Scale,
+            "Activity Tracker" => DeviceType::ActivityTracker,
+            "Blood Pressure Monitor" => DeviceType::BloodPressureMonitor,
+            "Smart Connected Thermometer" => DeviceType::Thermometer,
+            "Sleep Monitor" => DeviceType::SleepMonitor,
+            "babyphone" => DeviceType::Babyphone,
+            name => DeviceType::Unknown(name.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Serializes as the `type` value used by the API.
+impl Serialize for DeviceType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes from the `type` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for DeviceType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|name| DeviceType::from_name(&name))
+    }
+}
+
+/// Battery level of a device, from the `battery` field
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BatteryLevel {
+    High,
+    Medium,
+    Low,
+    /// A `battery` value this library does not know
+    Unknown(String),
+}
+
+impl BatteryLevel {
+    /// Returns the `battery` value used by the API.
+    pub fn as_str(&self) -> &str {
+        match self {
+            BatteryLevel::High => "high",
+            BatteryLevel::Medium => "medium",
+            BatteryLevel::Low => "low",
+            BatteryLevel::Unknown(level) => level,
+        }
+    }
+
+    /// Returns the `BatteryLevel` for a `battery` value, `Unknown` if the value is not known.
+    pub fn from_name(level: &str) -> Self {
+        match level {
+            "high" => BatteryLevel::High,
+            "medium" => BatteryLevel::Medium,
+            "low" => BatteryLevel::Low,
+            level => BatteryLevel::Unknown(level.to_string()),
+        }
+    }
+}
+
+/// Serializes as the `battery` value used by the API.
+impl Serialize for BatteryLevel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes from the `battery` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for BatteryLevel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|level| BatteryLevel::from_name(&level))
+    }
+}
+
+/// Withings model ids and the name of the device, from the API reference
+const MODELS: &[(i64, &str)] = &[
+    (1, "Withings WBS01"),
+    (2, "WS30"),
+    (3, "Kid Scale"),
+    (4, "Smart Body Analyzer"),
+    (5, "Body+"),
+    (6, "Body Cardio"),
+    (7, "Body"),
+    (9, "Body Pro"),
+    (10, "Body Scan"),
+    (11, "WBS10"),
+    (12, "WBS11"),
+    (13, "Body+"),
+    (21, "Smart Baby Monitor"),
+    (22, "Withings Home"),
+    (41, "Withings Blood Pressure Monitor V1"),
+    (42, "Withings Blood Pressure Monitor V2"),
+    (43, "Withings Blood Pressure Monitor V3"),
+    (44, "BPM Core"),
+    (45, "BPM Connect"),
+    (46, "BPM Connect Pro"),
+    (51, "Pulse"),
+    (52, "Activite"),
+    (53, "Activite (Pop, Steel)"),
+    (54, "Withings Go"),
+    (55, "Activite Steel HR"),
+    (58, "Pulse HR"),
+    (59, "Activite Steel HR Sport Edition"),
+    (60, "Aura dock"),
+    (61, "Aura sensor"),
+    (62, "Aura dock"),
+    (63, "Sleep sensor"),
+    (70, "Thermo"),
+    (90, "Move"),
+    (91, "Move ECG"),
+    (92, "Move ECG"),
+    (93, "ScanWatch"),
+];
+
+/// Returns the name of a device from its Withings model id, `None` if the id is not known.
+///
+/// # Examples
+///
+/// ```
+/// use withings_rs::models::device::model_name;
+///
+/// assert_eq!(model_name(6), Some("Body Cardio"));
+/// assert_eq!(model_name(-1), None);
+/// ```
+pub fn model_name(modelid: i64) -> Option<&'static str> {
+    MODELS
+        .iter()
+        .find(|(id, _)| *id == modelid)
+        .map(|(_, name)| *name)
+}
+
+impl Measuregrp {
+    /// Returns a human readable name of the device that captured the group: the `model` sent by
+    /// Withings, or the catalogue name of the `modelid`. `None` for manual entries.
+    pub fn device_name(&self) -> Option<&str> {
+        self.model().or_else(|| self.modelid().and_then(model_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn device(deviceid: &str, hash_deviceid: Option<&str>) -> Device {
+        serde_json::from_value(json!({
+            "type": "Scale",
+            "model": "Body Cardio",
+            "model_id": 6,
+            "battery": "high",
+            "deviceid": deviceid,
+            "hash_deviceid": hash_deviceid
+        }))
+        .unwrap()
+    }
+
+    fn group(device: Value) -> Measuregrp {
+        let mut group = json!({
+            "grpid": 1,
+            "attrib": 0,
+            "date": 1706083263,
+            "created": 1706083263,
+            "modified": 1706083263,
+            "category": 1,
+            "measures": [],
+            "comment": null
+        });
+        group
+            .as_object_mut()
+            .unwrap()
+            .extend(device.as_object().unwrap().clone());
+        serde_json::from_value(group).unwrap()
+    }
+
+    #[test]
+    fn device_type_and_battery_keep_unknown_values() {
+        for name in [
+            "Scale",
+            "Activity Tracker",
+            "Smart Connected Thermometer",
+            "babyphone",
+        ] {
+            assert_eq!(DeviceType::from_name(name).as_str(), name);
+        }
+        let device_type: DeviceType = serde_json::from_value(json!("Smart Mirror")).unwrap();
+        assert_eq!(device_type, DeviceType::Unknown("Smart Mirror".to_string()));
+        assert_eq!(
+            serde_json::to_value(&device_type).unwrap(),
+            json!("Smart Mirror")
+        );
+
+        assert_eq!(BatteryLevel::from_name("medium"), BatteryLevel::Medium);
+        let battery: BatteryLevel = serde_json::from_value(json!("critical")).unwrap();
+        assert_eq!(battery, BatteryLevel::Unknown("critical".to_string()));
+        assert_eq!(serde_json::to_value(&battery).unwrap(), json!("critical"));
+    }
+
+    #[test]
+    fn captured_prefers_the_hashed_device_id() {
+        let devices = [device("a", Some("hash-a")), device("b", Some("hash-b"))];
+
+        // The hashes decide when both sides have one, even if the plain ids disagree
+        let hashed = group(json!({ "deviceid": "a", "hash_deviceid": "hash-b" }));
+        assert_eq!(find_device(&devices, &hashed).unwrap().deviceid, "b");
+
+        // Without a hash on the group, the plain ids are compared
+        let plain = group(json!({ "deviceid": "a" }));
+        assert_eq!(find_device(&devices, &plain).unwrap().deviceid, "a");
+
+        let manual = group(json!({}));
+        assert!(find_device(&devices, &manual).is_none());
+    }
+
+    #[test]
+    fn captured_falls_back_to_the_device_id_without_a_device_hash() {
+        let device = device("a", None);
+        assert!(device.captured(&group(json!({ "deviceid": "a", "hash_deviceid": "x" }))));
+        assert!(!device.captured(&group(json!({ "deviceid": "b" }))));
+    }
+
+    #[test]
+    fn device_name_uses_the_model_then_the_catalogue() {
+        let named = group(json!({ "model": "Body+", "modelid": 6 }));
+        assert_eq!(named.device_name(), Some("Body+"));
+
+        let catalogued = group(json!({ "modelid": 93 }));
+        assert_eq!(catalogued.device_name(), Some("ScanWatch"));
+
+        assert_eq!(group(json!({ "modelid": 999 })).device_name(), None);
+        assert_eq!(group(json!({})).device_name(), None);
+    }
+
+    #[test]
+    fn model_ids_are_unique() {
+        for (i, (id, _)) in MODELS.iter().enumerate() {
+            assert!(MODELS[i + 1..].iter().all(|(other, _)| other != id));
+            assert!(model_name(*id).is_some());
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! # Goals model
+//! Docs: https://developer.withings.com/api-reference/#tag/user/operation/userv2-getgoals
+//! Response body from the user v2 getgoals endpoint, and the user objectives sent by getmeas as
+//! measure groups of category 2.
+
+use crate::models::de::seconds;
+use crate::models::meas::{CategoryType, MeasureType, Measuregrp};
+use crate::models::unit::{Quantity, Unit};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Response from the API is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseGoals {
+    pub status: i64,
+    pub body: GoalsBody,
+}
+
+/// The body of the response is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoalsBody {
+    pub goals: Goals,
+}
+
+/// The goals set by the user. Goals the user did not set are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Goals {
+    /// Daily steps
+    #[serde(default)]
+    pub steps: Option<i64>,
+    /// Nightly sleep, in seconds
+    #[serde(default)]
+    pub sleep: Option<i64>,
+    #[serde(default)]
+    pub weight: Option<WeightGoal>,
+}
+
+impl Goals {
+    /// Returns the nightly sleep goal.
+    pub fn sleep_duration(&self) -> Option<Duration> {
+        seconds(self.sleep)
+    }
+
+    /// Returns the decoded weight goal.
+    pub fn weight_quantity(&self) -> Option<Quantity> {
+        self.weight.map(|weight| weight.quantity())
+    }
+
+    /// Returns the share of the daily steps goal reached by `steps`, e.g. `0.5` halfway.
+    pub fn steps_progress(&self, steps: i64) -> Option<f64> {
+        let goal = self.steps.filter(|goal| *goal > 0)?;
+        Some(steps as f64 / goal as f64)
+    }
+}
+
+/// The weight goal, encoded like a measure: `value × 10^unit` kilograms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeightGoal {
+    pub value: i64,
+    pub unit: i64,
+}
+
+impl WeightGoal {
+    /// Returns the decoded weight.
+    pub fn quantity(&self) -> Quantity {
+        Quantity {
+            value: self.value,
+            exponent: self.unit,
+            unit: Unit::Kilogram,
+        }
+    }
+}
+
+/// A user objective, a target value for a measure type, from a measure group of category 2
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Objective {
+    pub grpid: i64,
+    /// When the objective was set, as epoch seconds
+    pub date: i64,
+    pub measure_type: MeasureType,
+    pub target: Quantity,
+}
+
+impl Objective {
+    /// Returns the objectives of a measure group, none if the group holds real measures.
+    pub fn from_group(group: &Measuregrp) -> Vec<Objective> {
+        if group.category() != Some(CategoryType::UserObjections) {
+            return Vec::new();
+        }
+        group
+            .measures
+            .iter()
+            .map(|measure| Objective {
+                grpid: group.grpid(),
+                date: group.date(),
+                measure_type: measure.measure_type,
+                target: measure.quantity(),
+            })
+            .collect()
+    }
+
+    /// Returns what is left to reach the target from `current`, in the unit of the target.
+    /// Negative when `current` is above the target. `None` if the units differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use withings_rs::models::goals::Objective;
+    /// use withings_rs::models::{MeasureType, Quantity, Unit};
+    ///
+    /// let kg = |value| Quantity { value, exponent: -1, unit: Unit::Kilogram };
+    /// let objective = Objective {
+    ///     grpid: 1,
+    ///     date: 0,
+    ///     measure_type: MeasureType::Weight,
+    ///     target: kg(700),
+    /// };
+    /// assert_eq!(objective.remaining(kg(725)), Some(-2.5));
+    /// ```
+    pub fn remaining(&self, current: Quantity) -> Option<f64> {
+        (current.unit == self.target.unit).then(|| self.target.as_f64() - current.as_f64())
+    }
+}
+
+impl Measuregrp {
+    /// Returns `true` if the group holds user objectives rather than real measures.
+    pub fn is_objective(&self) -> bool {
+        self.category() == Some(CategoryType::UserObjections)
+    }
+
+    /// Returns the user objectives of the group, none if the group holds real measures.
+    pub fn objectives(&self) -> Vec<Objective> {
+        Objective::from_group(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn group(category: i64) -> Measuregrp {
+        serde_json::from_value(json!({
+            "grpid": 42,
+            "attrib": 0,
+            "date": 1706083263,
+            "created": 1706083263,
+            "modified": 1706083263,
+            "category": category,
+            "measures": [
+                { "value": 70000, "type": 1, "unit": -3, "algo": 0, "fm": 0 },
+                { "value": 18, "type": 6, "unit": 0, "algo": 0, "fm": 0 }
+            ],
+            "comment": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn objectives_come_from_category_2_groups() {
+        let group = group(2);
+        assert!(group.is_objective());
+
+        let objectives = group.objectives();
+        assert_eq!(objectives, Objective::from_group(&group));
+        assert_eq!(objectives.len(), 2);
+        assert_eq!(objectives[0].grpid, 42);
+        assert_eq!(objectives[0].date, 1706083263);
+        assert_eq!(objectives[0].measure_type, MeasureType::Weight);
+        assert_eq!(objectives[0].target.as_f64(), 70.0);
+        assert_eq!(objectives[1].measure_type, MeasureType::FatRatio);
+    }
+
+    #[test]
+    fn real_measures_have_no_objectives() {
+        let group = group(1);
+        assert!(!group.is_objective());
+        assert!(group.objectives().is_empty());
+    }
+
+    #[test]
+    fn remaining_needs_the_same_unit() {
+        let objective = group(2).objectives()[0];
+        let kg = |value| Quantity {
+            value,
+            exponent: -1,
+            unit: Unit::Kilogram,
+        };
+        assert_eq!(objective.remaining(kg(725)), Some(-2.5));
+        assert_eq!(objective.remaining(kg(680)), Some(2.0));
+        let percent = Quantity {
+            value: 20,
+            exponent: 0,
+            unit: Unit::Percent,
+        };
+        assert_eq!(objective.remaining(percent), None);
+    }
+
+    #[test]
+    fn goals_without_a_weight_goal() {
+        let body: GoalsBody = serde_json::from_value(json!({
+            "goals": { "steps": 10000, "sleep": 28800 }
+        }))
+        .unwrap();
+        let goals = body.goals;
+        assert_eq!(goals.weight, None);
+        assert_eq!(goals.weight_quantity(), None);
+        assert_eq!(
+            goals.sleep_duration(),
+            Some(Duration::from_secs(8 * 60 * 60))
+        );
+        assert_eq!(goals.steps_progress(5000), Some(0.5));
+        assert_eq!(goals.steps_progress(12000), Some(1.2));
+    }
+
+    #[test]
+    fn steps_progress_needs_a_positive_goal() {
+        assert_eq!(Goals::default().steps_progress(5000), None);
+        let zero = Goals {
+            steps: Some(0),
+            ..Goals::default()
+        };
+        assert_eq!(zero.steps_progress(5000), None);
+
+        let goals: Goals = serde_json::from_value(json!({
+            "weight": { "value": 70500, "unit": -3 }
+        }))
+        .unwrap();
+        assert_eq!(goals.weight_quantity().unwrap().as_f64(), 70.5);
+        assert_eq!(goals.sleep_duration(), None);
+    }
+}
@@ -0,0 +1,200 @@
+//! # Heart model
+//! Docs: https://developer.withings.com/api-reference/#tag/heart
+//! Response bodies from the heart v2 list endpoint, the ECG recordings of BPM Core and ScanWatch
+//! devices, and the heart v2 get endpoint, the signal of one recording
+
+use crate::models::de;
+use crate::models::meas::BodyPosition;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+
+/// Response from the heart v2 list endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseHeartList {
+    pub status: i64,
+    pub body: HeartListBody,
+}
+
+/// The body of a heart list response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartListBody {
+    pub series: Vec<HeartRecording>,
+    /// Whether more recordings are available past this page
+    #[serde(default, deserialize_with = "de::bool_or_int")]
+    pub more: bool,
+    /// Offset to request the next page with, set when `more` is true
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+/// An ECG recording, with the blood pressure measured at the same time by a BPM Core
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartRecording {
+    #[serde(default)]
+    pub deviceid: Option<String>,
+    /// Model id of the device that made the recording
+    #[serde(default)]
+    pub model: Option<i64>,
+    pub ecg: Ecg,
+    #[serde(default)]
+    pub bloodpressure: Option<LinkedBloodPressure>,
+    /// Heart rate in beats per minute
+    #[serde(default)]
+    pub heart_rate: Option<i64>,
+    /// When the recording was made, as epoch seconds
+    pub timestamp: i64,
+    /// IANA timezone of the user
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// The ECG of a recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ecg {
+    /// Id of the signal, to get it with `heart::get_signal`
+    pub signalid: i64,
+    pub afib: AfibClassification,
+}
+
+/// Blood pressure in mmHg measured with an ECG recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedBloodPressure {
+    pub diastole: i64,
+    pub systole: i64,
+}
+
+/// Atrial fibrillation classification of an ECG, from the `afib` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AfibClassification {
+    /// No sign of atrial fibrillation
+    Negative,
+    /// Signs of atrial fibrillation
+    Positive,
+    /// The recording could not be classified
+    Inconclusive,
+    /// An `afib` value this library does not know
+    Unknown(i64),
+}
+
+impl AfibClassification {
+    /// Returns the `afib` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            AfibClassification::Negative => 0,
+            AfibClassification::Positive => 1,
+            AfibClassification::Inconclusive => 2,
+            AfibClassification::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `AfibClassification` for an `afib` value, `Unknown` if the value is not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            0 => AfibClassification::Negative,
+            1 => AfibClassification::Positive,
+            2 => AfibClassification::Inconclusive,
+            id => AfibClassification::Unknown(id),
+        }
+    }
+}
+
+/// Serializes as the `afib` value used by the API.
+impl Serialize for AfibClassification {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `afib` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for AfibClassification {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(AfibClassification::from_id)
+    }
+}
+
+/// Response from the heart v2 get endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseHeartSignal {
+    pub status: i64,
+    pub body: HeartSignal,
+}
+
+/// The raw signal of an ECG recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartSignal {
+    /// Raw samples, in microvolts
+    pub signal: Vec<i64>,
+    /// Samples per second, in Hz
+    pub sampling_frequency: i64,
+    /// Where the device was worn during the recording
+    #[serde(default)]
+    pub wearposition: Option<BodyPosition>,
+}
+
+impl HeartSignal {
+    /// Returns the samples in microvolts.
+    pub fn microvolts(&self) -> Vec<f64> {
+        self.signal.iter().map(|&sample| sample as f64).collect()
+    }
+
+    /// Returns the samples with their offset from the start of the recording.
+    pub fn samples(&self) -> impl Iterator<Item = (Duration, f64)> + '_ {
+        de::signal_samples(&self.signal, self.sampling_frequency)
+    }
+
+    /// Returns the length of the recording.
+    pub fn duration(&self) -> Duration {
+        de::signal_duration(&self.signal, self.sampling_frequency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn afib_round_trips_through_its_id() {
+        for afib in [
+            AfibClassification::Negative,
+            AfibClassification::Positive,
+            AfibClassification::Inconclusive,
+            AfibClassification::Unknown(7),
+        ] {
+            assert_eq!(AfibClassification::from_id(afib.id()), afib);
+            let value = serde_json::to_value(afib).unwrap();
+            assert_eq!(value, json!(afib.id()));
+            assert_eq!(
+                serde_json::from_value::<AfibClassification>(value).unwrap(),
+                afib
+            );
+        }
+    }
+
+    #[test]
+    fn samples_carry_their_offset() {
+        let signal: HeartSignal = serde_json::from_value(json!({
+            "signal": [10, -20, 30, 40],
+            "sampling_frequency": 4,
+            "wearposition": 0
+        }))
+        .unwrap();
+        let samples: Vec<_> = signal.samples().collect();
+        assert_eq!(samples[0], (Duration::ZERO, 10.0));
+        assert_eq!(samples[1], (Duration::from_millis(250), -20.0));
+        assert_eq!(signal.duration(), Duration::from_secs(1));
+        assert_eq!(signal.microvolts(), vec![10.0, -20.0, 30.0, 40.0]);
+    }
+
+    #[test]
+    fn zero_sampling_frequency_does_not_divide_by_zero() {
+        let signal = HeartSignal {
+            signal: vec![1, 2, 3],
+            sampling_frequency: 0,
+            wearposition: None,
+        };
+        let offsets: Vec<_> = signal.samples().map(|(offset, _)| offset).collect();
+        assert_eq!(offsets[2], Duration::from_secs(2));
+        assert_eq!(signal.duration(), Duration::from_secs(3));
+    }
+}
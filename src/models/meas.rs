@@ -3,149 +3,391 @@
 //! Example: https://developer.withings.com/oauth2/#section/Measure/Get-measure
 //! Response body from the measure-getmeas endpoint
 
-use serde::{Deserialize, Serialize};
+use crate::models::de;
+use crate::models::unit::{Converted, Quantity, UnitPreferences, WithUnits};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
+pub use crate::models::measure_type::{MeasureCategory, MeasureType, UnknownMeasureType};
+
 /// Response from the API is a JSON object that includes the following fields:
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseMeas {
     pub status: i64,
     pub body: Body,
 }
 
 /// The body of the response is a JSON object that includes the following fields:
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Body {
     updatetime: i64,
     timezone: String,
     pub measuregrps: Vec<Measuregrp>,
+    /// Whether more measure groups are available past this page
+    #[serde(default, deserialize_with = "de::bool_or_int")]
+    pub more: bool,
+    /// Offset to request the next page with, set when `more` is true
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+impl Body {
+    /// Returns the time of the response as epoch seconds, to use as the next `lastupdate`.
+    pub fn updatetime(&self) -> i64 {
+        self.updatetime
+    }
+
+    /// Returns the IANA timezone of the user, e.g. `Europe/Paris`.
+    pub fn timezone(&self) -> &str {
+        &self.timezone
+    }
 }
 
 /// Struct collection of measures
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Measuregrp {
     grpid: i64,
-    attrib: i64,
+    attrib: Attribution,
     date: i64,
     created: i64,
     modified: i64,
     category: i64,
-    deviceid: String,
-    hash_deviceid: String,
+    // Manual entries have no device, so these fields can be null
+    #[serde(default)]
+    deviceid: Option<String>,
+    #[serde(default)]
+    hash_deviceid: Option<String>,
     pub measures: Vec<Measure>,
-    modelid: i64,
-    model: String,
+    #[serde(default)]
+    modelid: Option<i64>,
+    #[serde(default)]
+    model: Option<String>,
     comment: Option<serde_json::Value>,
 }
 
+impl Measuregrp {
+    /// Returns the unique id of the measure group.
+    pub fn grpid(&self) -> i64 {
+        self.grpid
+    }
+
+    /// Returns how the measure group was captured and attributed to the user.
+    pub fn attribution(&self) -> Attribution {
+        self.attrib
+    }
+
+    /// Returns when the measures were taken, as epoch seconds.
+    pub fn date(&self) -> i64 {
+        self.date
+    }
+
+    /// Returns when the measure group was stored, as epoch seconds.
+    pub fn created(&self) -> i64 {
+        self.created
+    }
+
+    /// Returns when the measure group was last modified, as epoch seconds.
+    pub fn modified(&self) -> i64 {
+        self.modified
+    }
+
+    /// Returns whether the group holds real measures or user objectives, `None` for a category
+    /// this library does not know.
+    pub fn category(&self) -> Option<CategoryType> {
+        CategoryType::from_id(self.category)
+    }
+
+    /// Returns the id of the device that took the measures, `None` for manual entries.
+    pub fn deviceid(&self) -> Option<&str> {
+        self.deviceid.as_deref()
+    }
+
+    /// Returns the hashed id of the device that took the measures, `None` for manual entries.
+    pub fn hash_deviceid(&self) -> Option<&str> {
+        self.hash_deviceid.as_deref()
+    }
+
+    /// Returns the model id of the device that took the measures.
+    pub fn modelid(&self) -> Option<i64> {
+        self.modelid
+    }
+
+    /// Returns the model name of the device that took the measures.
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
+    /// Returns the comment attached to the measure group.
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_ref().and_then(|comment| comment.as_str())
+    }
+
+    /// Returns the first measure of the given type in the group.
+    pub fn measure(&self, measure_type: MeasureType) -> Option<&Measure> {
+        self.measures
+            .iter()
+            .find(|measure| measure.measure_type == measure_type)
+    }
+}
+
+/// How a measure group was captured, from the `attrib` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attribution {
+    /// Captured by a device and known to belong to the user
+    Device,
+    /// Captured by a device but may belong to other users as well
+    DeviceAmbiguous,
+    /// Entered manually by the user
+    Manual,
+    /// Entered manually during user creation, may not be accurate
+    ManualAtCreation,
+    /// Best value computed by a Blood Pressure Monitor from a series of measures
+    DeviceAuto,
+    /// Confirmed by the user
+    UserConfirmed,
+    /// Captured by a device, same as `Device`
+    DeviceSynced,
+    /// Performed in specific guided conditions, e.g. a Nerve Health Score
+    GuidedConditions,
+    /// Performed in specific guided conditions, for Nerve Health and Electrodermal activity scores
+    GuidedConditionsEda,
+    /// An `attrib` value this library does not know
+    Unknown(i64),
+}
+
+impl Attribution {
+    /// Returns the `attrib` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            Attribution::Device => 0,
+            Attribution::DeviceAmbiguous => 1,
+            Attribution::Manual => 2,
+            Attribution::ManualAtCreation => 4,
+            Attribution::DeviceAuto => 5,
+            Attribution::UserConfirmed => 7,
+            Attribution::DeviceSynced => 8,
+            Attribution::GuidedConditions => 15,
+            Attribution::GuidedConditionsEda => 17,
+            Attribution::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `Attribution` for an `attrib` value, `Unknown` if the value is not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            0 => Attribution::Device,
+            1 => Attribution::DeviceAmbiguous,
+            2 => Attribution::Manual,
+            4 => Attribution::ManualAtCreation,
+            5 => Attribution::DeviceAuto,
+            7 => Attribution::UserConfirmed,
+            8 => Attribution::DeviceSynced,
+            15 => Attribution::GuidedConditions,
+            17 => Attribution::GuidedConditionsEda,
+            id => Attribution::Unknown(id),
+        }
+    }
+
+    /// Returns `true` if the measures were captured by a device without ambiguity.
+    pub fn is_device(&self) -> bool {
+        matches!(
+            self,
+            Attribution::Device
+                | Attribution::DeviceAuto
+                | Attribution::DeviceSynced
+                | Attribution::GuidedConditions
+                | Attribution::GuidedConditionsEda
+        )
+    }
+
+    /// Returns `true` if the measures may belong to another user.
+    pub fn is_ambiguous(&self) -> bool {
+        matches!(self, Attribution::DeviceAmbiguous)
+    }
+
+    /// Returns `true` if the measures were entered by hand.
+    pub fn is_manual(&self) -> bool {
+        matches!(self, Attribution::Manual | Attribution::ManualAtCreation)
+    }
+}
+
+/// Serializes as the `attrib` value used by the API.
+impl Serialize for Attribution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `attrib` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for Attribution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(Attribution::from_id)
+    }
+}
+
+/// Body segment of a measure, from the `position` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BodyPosition {
+    RightWrist,
+    LeftWrist,
+    RightArm,
+    LeftArm,
+    RightFoot,
+    LeftFoot,
+    BetweenLegs,
+    /// Left part of the body
+    LeftBody,
+    /// Right part of the body
+    RightBody,
+    LeftLeg,
+    RightLeg,
+    Torso,
+    LeftHand,
+    RightHand,
+    /// A `position` value this library does not know
+    Unknown(i64),
+}
+
+impl BodyPosition {
+    /// Returns the `position` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            BodyPosition::RightWrist => 0,
+            BodyPosition::LeftWrist => 1,
+            BodyPosition::RightArm => 2,
+            BodyPosition::LeftArm => 3,
+            BodyPosition::RightFoot => 4,
+            BodyPosition::LeftFoot => 5,
+            BodyPosition::BetweenLegs => 6,
+            BodyPosition::LeftBody => 8,
+            BodyPosition::RightBody => 9,
+            BodyPosition::LeftLeg => 10,
+            BodyPosition::RightLeg => 11,
+            BodyPosition::Torso => 12,
+            BodyPosition::LeftHand => 13,
+            BodyPosition::RightHand => 14,
+            BodyPosition::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `BodyPosition` for a `position` value, `Unknown` if the value is not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            0 => BodyPosition::RightWrist,
+            1 => BodyPosition::LeftWrist,
+            2 => BodyPosition::RightArm,
+            3 => BodyPosition::LeftArm,
+            4 => BodyPosition::RightFoot,
+            5 => BodyPosition::LeftFoot,
+            6 => BodyPosition::BetweenLegs,
+            8 => BodyPosition::LeftBody,
+            9 => BodyPosition::RightBody,
+            10 => BodyPosition::LeftLeg,
+            11 => BodyPosition::RightLeg,
+            12 => BodyPosition::Torso,
+            13 => BodyPosition::LeftHand,
+            14 => BodyPosition::RightHand,
+            id => BodyPosition::Unknown(id),
+        }
+    }
+}
+
+/// Serializes as the `position` value used by the API.
+impl Serialize for BodyPosition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `position` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for BodyPosition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(BodyPosition::from_id)
+    }
+}
+
 /// Struct for each measure fields
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Measure {
     pub value: i64,
     #[serde(rename = "type")]
-    pub measure_type: i64,
+    pub measure_type: MeasureType,
     pub unit: i64,
     algo: i64,
     fm: i64,
+    /// Body segment of the measure, only sent by segmental scales
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<BodyPosition>,
+}
+
+impl Measure {
+    /// Returns the decoded value, `value × 10^unit`, with the unit implied by the measure type.
+    pub fn quantity(&self) -> Quantity {
+        Quantity {
+            value: self.value,
+            exponent: self.unit,
+            unit: self.measure_type.unit(),
+        }
+    }
+
+    /// Returns the decoded value, `value × 10^unit`, as a float.
+    pub fn decoded_value(&self) -> f64 {
+        self.quantity().as_f64()
+    }
+
+    /// Returns the decoded value in the units chosen by `preferences`.
+    pub fn convert(&self, preferences: &UnitPreferences) -> Converted {
+        self.quantity().convert(preferences)
+    }
+
+    /// Returns a `Display` adapter that shows the value in the units chosen by `preferences`.
+    pub fn display_with<'a>(&'a self, preferences: &'a UnitPreferences) -> WithUnits<'a, Self> {
+        WithUnits {
+            value: self,
+            preferences,
+        }
+    }
+}
+
+/// Displays the decoded value with its unit, e.g. `72.35 kg`.
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_with(&UnitPreferences::default()))
+    }
+}
+
+/// Displays the decoded value in the preferred unit, e.g. `159.5 lb`.
+impl fmt::Display for WithUnits<'_, Measure> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value.convert(self.preferences))
+    }
 }
 
 /// CategoryType enum for the category field in the measure struct matches the values in the Withings API docs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CategoryType {
     Measures = 1,
     // User Measuers
     UserObjections = 2, // User Objectives
 }
 
-/// Implement Display for CategoryType enum so to_string() can be used to convert to the string value expected by the API
-impl fmt::Display for CategoryType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            CategoryType::Measures => write!(f, "1"),
-            CategoryType::UserObjections => write!(f, "2"),
+impl CategoryType {
+    /// Returns the `CategoryType` for a category value sent by the API.
+    pub fn from_id(id: i64) -> Option<Self> {
+        match id {
+            1 => Some(CategoryType::Measures),
+            2 => Some(CategoryType::UserObjections),
+            _ => None,
         }
     }
 }
 
-/// MeasureType enum for the measure type field in the measure struct matches the values in the Withings API docs
-pub enum MeasureType {
-    Weight = 1,
-    // Weight (kg)
-    Height = 4,
-    // Height (meter)
-    FatFreeMass = 5,
-    // Fat Free Mass (kg)
-    FatRatio = 6,
-    // Fat Ratio (%)
-    FatMassWeight = 8,
-    // Fat Mass Weight (kg)
-    DiastolicBloodPressure = 9, // Diastolic Blood Pressure (mmHg)
-    SystolicBloodPressure = 10, // Systolic Blood Pressure (mmHg)
-    HeartPulse = 11,
-    // Heart Pulse (bpm)
-    Temperature = 12,
-    // Temperature (C)
-    Sp02 = 54,
-    // SpO2 (%)
-    BodyTemperature = 71,
-    // Body Temperature (C)
-    SkinTemperature = 73,
-    // Skin Temperature (C)
-    MuscleMass = 76,
-    // Muscle Mass (kg)
-    Hydration = 77,
-    // Hydration (%)
-    BoneMass = 88,
-    // Bone Mass (kg)
-    PulseWaveVelocity = 91,
-    // Pulse Wave Velocity (m/s)
-    V02Max = 123,
-    // VO2 max (mL/min/kg)
-    AtrialFibrillation = 130,
-    // Atrial Fibrillation (0 or 1)
-    Qrs = 135,
-    // QRS Duration (ms)
-    VascularAge = 155,
-    // Vascular Age (years)
-    ExtracellularWater = 168,
-    // Extracellular Water (kg)
-    IntracellularWater = 169,
-    // Intracellular Water (kg)
-    VisceralFatMass = 170,
-    // Visceral Fat Mass (kg)
-    FatMass = 174,
-    // Fat Mass (kg)
-    MuscleMassSegments = 175, // Muscle Mass (kg)
-}
-
-/// Implement Display for MeasureType enum so to_string() can be used to convert to the string value expected by the API
-impl fmt::Display for MeasureType {
+/// Implement Display for CategoryType enum so to_string() can be used to convert to the string value expected by the API
+impl fmt::Display for CategoryType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            MeasureType::Weight => write!(f, "1"),
-            MeasureType::Height => write!(f, "4"),
-            MeasureType::FatFreeMass => write!(f, "5"),
-            MeasureType::FatRatio => write!(f, "6"),
-            MeasureType::FatMassWeight => write!(f, "8"),
-            MeasureType::DiastolicBloodPressure => write!(f, "9"),
-            MeasureType::SystolicBloodPressure => write!(f, "10"),
-            MeasureType::HeartPulse => write!(f, "11"),
-            MeasureType::Temperature => write!(f, "12"),
-            MeasureType::Sp02 => write!(f, "54"),
-            MeasureType::BodyTemperature => write!(f, "71"),
-            MeasureType::SkinTemperature => write!(f, "73"),
-            MeasureType::MuscleMass => write!(f, "76"),
-            MeasureType::Hydration => write!(f, "77"),
-            MeasureType::BoneMass => write!(f, "88"),
-            MeasureType::PulseWaveVelocity => write!(f, "91"),
-            MeasureType::V02Max => write!(f, "123"),
-            MeasureType::AtrialFibrillation => write!(f, "130"),
-            MeasureType::Qrs => write!(f, "135"),
-            MeasureType::VascularAge => write!(f, "155"),
-            MeasureType::ExtracellularWater => write!(f, "168"),
-            MeasureType::IntracellularWater => write!(f, "169"),
-            MeasureType::VisceralFatMass => write!(f, "170"),
-            MeasureType::FatMass => write!(f, "174"),
-            MeasureType::MuscleMassSegments => write!(f, "175"),
+            CategoryType::Measures => write!(f, "1"),
+            CategoryType::UserObjections => write!(f, "2"),
         }
     }
 }
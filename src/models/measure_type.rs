@@ -0,0 +1,456 @@
+//! # Measure types
+//! Docs: https://developer.withings.com/api-reference/#tag/measure/operation/measure-getmeas
+//! The `type` field of a measure, with its human name, unit and category.
+//!
+//! Withings adds new measure types over time. Ids this library does not know are kept as
+//! `MeasureType::Unknown` so they never break deserialization.
+//!
+//! # Examples
+//!
+//! ```
+//! use withings_rs::models::{MeasureType, Unit};
+//!
+//! assert_eq!("fat ratio".parse::<MeasureType>().unwrap(), MeasureType::FatRatio);
+//! assert_eq!("226".parse::<MeasureType>().unwrap(), MeasureType::BasalMetabolicRate);
+//! assert_eq!(MeasureType::try_from(1).unwrap().unit(), Unit::Kilogram);
+//! assert_eq!(MeasureType::from_id(9999), MeasureType::Unknown(9999));
+//! assert!("9999".parse::<MeasureType>().is_err());
+//! ```
+
+use crate::models::unit::Unit;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// MeasureType enum for the measure type field in the measure struct matches the values in the Withings API docs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeasureType {
+    /// Weight (kg)
+    Weight,
+    /// Height (meter)
+    Height,
+    /// Fat Free Mass (kg)
+    FatFreeMass,
+    /// Fat Ratio (%)
+    FatRatio,
+    /// Fat Mass Weight (kg)
+    FatMassWeight,
+    /// Diastolic Blood Pressure (mmHg)
+    DiastolicBloodPressure,
+    /// Systolic Blood Pressure (mmHg)
+    SystolicBloodPressure,
+    /// Heart Pulse (bpm)
+    HeartPulse,
+    /// Temperature (C)
+    Temperature,
+    /// SpO2 (%)
+    Sp02,
+    /// Body Temperature (C)
+    BodyTemperature,
+    /// Skin Temperature (C)
+    SkinTemperature,
+    /// Muscle Mass (kg)
+    MuscleMass,
+    /// Hydration (kg)
+    Hydration,
+    /// Bone Mass (kg)
+    BoneMass,
+    /// Pulse Wave Velocity (m/s)
+    PulseWaveVelocity,
+    /// VO2 max (mL/min/kg)
+    V02Max,
+    /// Atrial Fibrillation result from ECG (0 or 1)
+    AtrialFibrillation,
+    /// QRS interval duration based on ECG signal (ms)
+    Qrs,
+    /// PR interval duration based on ECG signal (ms)
+    PrInterval,
+    /// QT interval duration based on ECG signal (ms)
+    QtInterval,
+    /// Corrected QT interval duration based on ECG signal (ms)
+    CorrectedQtInterval,
+    /// Atrial Fibrillation result from PPG (0 or 1)
+    AtrialFibrillationPpg,
+    /// Vascular Age (years)
+    VascularAge,
+    /// Nerve Health Score Conductance 2 electrodes Feet
+    NerveHealthScore,
+    /// Extracellular Water (kg)
+    ExtracellularWater,
+    /// Intracellular Water (kg)
+    IntracellularWater,
+    /// Visceral Fat (without unit)
+    VisceralFatMass,
+    /// Fat Free Mass for segments (kg)
+    FatFreeMassSegments,
+    /// Fat Mass for segments (kg)
+    FatMass,
+    /// Muscle Mass for segments (kg)
+    MuscleMassSegments,
+    /// Electrodermal activity feet (µS)
+    ElectrodermalActivityFeet,
+    /// Basal Metabolic Rate (kcal/day)
+    BasalMetabolicRate,
+    /// Metabolic Age (years)
+    MetabolicAge,
+    /// Electrochemical Skin Conductance (µS)
+    ElectrochemicalSkinConductance,
+    /// A type id this library does not know yet
+    Unknown(i64),
+}
+
+/// Groups measure types by what they describe. Not to be confused with `CategoryType`, which
+/// separates real measures from user objectives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeasureCategory {
+    /// Weight, height and body composition
+    Body,
+    /// Blood pressure, heart rate, ECG and arterial measures
+    Cardiovascular,
+    Temperature,
+    /// SpO2
+    Respiratory,
+    /// VO2 max
+    Fitness,
+    /// BMR and metabolic age
+    Metabolic,
+    /// Nerve health and skin conductance
+    Nerve,
+    Unknown,
+}
+
+/// Error returned when a type id or name does not match a known `MeasureType`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownMeasureType(pub String);
+
+impl fmt::Display for UnknownMeasureType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown measure type: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMeasureType {}
+
+impl MeasureType {
+    /// Every measure type known to this library, in id order.
+    pub const ALL: [MeasureType; 35] = [
+        MeasureType::Weight,
+        MeasureType::Height,
+        MeasureType::FatFreeMass,
+        MeasureType::FatRatio,
+        MeasureType::FatMassWeight,
+        MeasureType::DiastolicBloodPressure,
+        MeasureType::SystolicBloodPressure,
+        MeasureType::HeartPulse,
+        MeasureType::Temperature,
+        MeasureType::Sp02,
+        MeasureType::BodyTemperature,
+        MeasureType::SkinTemperature,
+        MeasureType::MuscleMass,
+        MeasureType::Hydration,
+        MeasureType::BoneMass,
+        MeasureType::PulseWaveVelocity,
+        MeasureType::V02Max,
+        MeasureType::AtrialFibrillation,
+        MeasureType::Qrs,
+        MeasureType::PrInterval,
+        MeasureType::QtInterval,
+        MeasureType::CorrectedQtInterval,
+        MeasureType::AtrialFibrillationPpg,
+        MeasureType::VascularAge,
+        MeasureType::NerveHealthScore,
+        MeasureType::ExtracellularWater,
+        MeasureType::IntracellularWater,
+        MeasureType::VisceralFatMass,
+        MeasureType::FatFreeMassSegments,
+        MeasureType::FatMass,
+        MeasureType::MuscleMassSegments,
+        MeasureType::ElectrodermalActivityFeet,
+        MeasureType::BasalMetabolicRate,
+        MeasureType::MetabolicAge,
+        MeasureType::ElectrochemicalSkinConductance,
+    ];
+
+    /// Returns the type id used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            MeasureType::Weight => 1,
+            MeasureType::Height => 4,
+            MeasureType::FatFreeMass => 5,
+            MeasureType::FatRatio => 6,
+            MeasureType::FatMassWeight => 8,
+            MeasureType::DiastolicBloodPressure => 9,
+            MeasureType::SystolicBloodPressure => 10,
+            MeasureType::HeartPulse => 11,
+            MeasureType::Temperature => 12,
+            MeasureType::Sp02 => 54,
+            MeasureType::BodyTemperature => 71,
+            MeasureType::SkinTemperature => 73,
+            MeasureType::MuscleMass => 76,
+            MeasureType::Hydration => 77,
+            MeasureType::BoneMass => 88,
+            MeasureType::PulseWaveVelocity => 91,
+            MeasureType::V02Max => 123,
+            MeasureType::AtrialFibrillation => 130,
+            MeasureType::Qrs => 135,
+            MeasureType::PrInterval => 136,
+            MeasureType::QtInterval => 137,
+            MeasureType::CorrectedQtInterval => 138,
+            MeasureType::AtrialFibrillationPpg => 139,
+            MeasureType::VascularAge => 155,
+            MeasureType::NerveHealthScore => 167,
+            MeasureType::ExtracellularWater => 168,
+            MeasureType::IntracellularWater => 169,
+            MeasureType::VisceralFatMass => 170,
+            MeasureType::FatFreeMassSegments => 173,
+            MeasureType::FatMass => 174,
+            MeasureType::MuscleMassSegments => 175,
+            MeasureType::ElectrodermalActivityFeet => 196,
+            MeasureType::BasalMetabolicRate => 226,
+            MeasureType::MetabolicAge => 227,
+            MeasureType::ElectrochemicalSkinConductance => 229,
+            MeasureType::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `MeasureType` for a type id sent by the API, `Unknown` if the id is not known.
+    pub fn from_id(id: i64) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|measure_type| measure_type.id() == id)
+            .unwrap_or(MeasureType::Unknown(id))
+    }
+
+    /// Returns `true` for ids this library does not know.
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, MeasureType::Unknown(_))
+    }
+
+    /// Returns the human readable name of the measure type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MeasureType::Weight => "Weight",
+            MeasureType::Height => "Height",
+            MeasureType::FatFreeMass => "Fat Free Mass",
+            MeasureType::FatRatio => "Fat Ratio",
+            MeasureType::FatMassWeight => "Fat Mass Weight",
+            MeasureType::DiastolicBloodPressure => "Diastolic Blood Pressure",
+            MeasureType::SystolicBloodPressure => "Systolic Blood Pressure",
+            MeasureType::HeartPulse => "Heart Pulse",
+            MeasureType::Temperature => "Temperature",
+            MeasureType::Sp02 => "SpO2",
+            MeasureType::BodyTemperature => "Body Temperature",
+            MeasureType::SkinTemperature => "Skin Temperature",
+            MeasureType::MuscleMass => "Muscle Mass",
+            MeasureType::Hydration => "Hydration",
+            MeasureType::BoneMass => "Bone Mass",
+            MeasureType::PulseWaveVelocity => "Pulse Wave Velocity",
+            MeasureType::V02Max => "VO2 Max",
+            MeasureType::AtrialFibrillation => "Atrial Fibrillation",
+            MeasureType::Qrs => "QRS Interval",
+            MeasureType::PrInterval => "PR Interval",
+            MeasureType::QtInterval => "QT Interval",
+            MeasureType::CorrectedQtInterval => "Corrected QT Interval",
+            MeasureType::AtrialFibrillationPpg => "Atrial Fibrillation PPG",
+            MeasureType::VascularAge => "Vascular Age",
+            MeasureType::NerveHealthScore => "Nerve Health Score",
+            MeasureType::ExtracellularWater => "Extracellular Water",
+            MeasureType::IntracellularWater => "Intracellular Water",
+            MeasureType::VisceralFatMass => "Visceral Fat",
+            MeasureType::FatFreeMassSegments => "Fat Free Mass Segments",
+            MeasureType::FatMass => "Fat Mass Segments",
+            MeasureType::MuscleMassSegments => "Muscle Mass Segments",
+            MeasureType::ElectrodermalActivityFeet => "Electrodermal Activity Feet",
+            MeasureType::BasalMetabolicRate => "Basal Metabolic Rate",
+            MeasureType::MetabolicAge => "Metabolic Age",
+            MeasureType::ElectrochemicalSkinConductance => "Electrochemical Skin Conductance",
+            MeasureType::Unknown(_) => "Unknown",
+        }
+    }
+
+    /// Returns the SI unit of the decoded values of this measure type.
+    pub fn unit(&self) -> Unit {
+        match self {
+            MeasureType::Weight
+            | MeasureType::FatFreeMass
+            | MeasureType::FatMassWeight
+            | MeasureType::MuscleMass
+            | MeasureType::Hydration
+            | MeasureType::BoneMass
+            | MeasureType::ExtracellularWater
+            | MeasureType::IntracellularWater
+            | MeasureType::FatFreeMassSegments
+            | MeasureType::FatMass
+            | MeasureType::MuscleMassSegments => Unit::Kilogram,
+            MeasureType::Height => Unit::Meter,
+            MeasureType::FatRatio | MeasureType::Sp02 => Unit::Percent,
+            MeasureType::DiastolicBloodPressure | MeasureType::SystolicBloodPressure => {
+                Unit::MillimeterOfMercury
+            }
+            MeasureType::HeartPulse => Unit::BeatsPerMinute,
+            MeasureType::Temperature
+            | MeasureType::BodyTemperature
+            | MeasureType::SkinTemperature => Unit::Celsius,
+            MeasureType::PulseWaveVelocity => Unit::MeterPerSecond,
+            MeasureType::V02Max => Unit::MilliliterPerMinutePerKilogram,
+            MeasureType::Qrs
+            | MeasureType::PrInterval
+            | MeasureType::QtInterval
+            | MeasureType::CorrectedQtInterval => Unit::Millisecond,
+            MeasureType::VascularAge | MeasureType::MetabolicAge => Unit::Year,
+            MeasureType::BasalMetabolicRate => Unit::KilocaloriePerDay,
+            MeasureType::ElectrodermalActivityFeet
+            | MeasureType::ElectrochemicalSkinConductance => Unit::Microsiemens,
+            MeasureType::AtrialFibrillation
+            | MeasureType::AtrialFibrillationPpg
+            | MeasureType::NerveHealthScore
+            | MeasureType::VisceralFatMass => Unit::Unitless,
+            MeasureType::Unknown(_) => Unit::Unknown,
+        }
+    }
+
+    /// Returns what the measure type describes.
+    pub fn category(&self) -> MeasureCategory {
+        match self {
+            MeasureType::Weight
+            | MeasureType::Height
+            | MeasureType::FatFreeMass
+            | MeasureType::FatRatio
+            | MeasureType::FatMassWeight
+            | MeasureType::MuscleMass
+            | MeasureType::Hydration
+            | MeasureType::BoneMass
+            | MeasureType::ExtracellularWater
+            | MeasureType::IntracellularWater
+            | MeasureType::VisceralFatMass
+            | MeasureType::FatFreeMassSegments
+            | MeasureType::FatMass
+            | MeasureType::MuscleMassSegments => MeasureCategory::Body,
+            MeasureType::DiastolicBloodPressure
+            | MeasureType::SystolicBloodPressure
+            | MeasureType::HeartPulse
+            | MeasureType::PulseWaveVelocity
+            | MeasureType::AtrialFibrillation
+            | MeasureType::Qrs
+            | MeasureType::PrInterval
+            | MeasureType::QtInterval
+            | MeasureType::CorrectedQtInterval
+            | MeasureType::AtrialFibrillationPpg
+            | MeasureType::VascularAge => MeasureCategory::Cardiovascular,
+            MeasureType::Temperature
+            | MeasureType::BodyTemperature
+            | MeasureType::SkinTemperature => MeasureCategory::Temperature,
+            MeasureType::Sp02 => MeasureCategory::Respiratory,
+            MeasureType::V02Max => MeasureCategory::Fitness,
+            MeasureType::BasalMetabolicRate | MeasureType::MetabolicAge => {
+                MeasureCategory::Metabolic
+            }
+            MeasureType::NerveHealthScore
+            | MeasureType::ElectrodermalActivityFeet
+            | MeasureType::ElectrochemicalSkinConductance => MeasureCategory::Nerve,
+            MeasureType::Unknown(_) => MeasureCategory::Unknown,
+        }
+    }
+}
+
+/// Implement Display for MeasureType enum so to_string() can be used to convert to the string value expected by the API
+impl fmt::Display for MeasureType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// Converts a type id, rejecting ids this library does not know.
+/// Use `MeasureType::from_id` to keep unknown ids as `MeasureType::Unknown`.
+impl TryFrom<i64> for MeasureType {
+    type Error = UnknownMeasureType;
+
+    fn try_from(id: i64) -> Result<Self, Self::Error> {
+        match MeasureType::from_id(id) {
+            MeasureType::Unknown(id) => Err(UnknownMeasureType(id.to_string())),
+            measure_type => Ok(measure_type),
+        }
+    }
+}
+
+/// Parses a type id such as `"1"`, or a name such as `"Weight"`, `"FatRatio"` or `"fat ratio"`.
+/// Names are matched without regard to case, spaces, `_` and `-`. Like `TryFrom<i64>`, ids this
+/// library does not know are rejected, use `MeasureType::from_id` to keep them.
+impl FromStr for MeasureType {
+    type Err = UnknownMeasureType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(id) = s.trim().parse::<i64>() {
+            return MeasureType::try_from(id);
+        }
+
+        let normalize = |name: &str| {
+            name.chars()
+                .filter(|c| !matches!(c, ' ' | '_' | '-'))
+                .collect::<String>()
+                .to_lowercase()
+        };
+        let wanted = normalize(s);
+        MeasureType::ALL
+            .into_iter()
+            .find(|measure_type| {
+                normalize(measure_type.name()) == wanted
+                    || normalize(&format!("{:?}", measure_type)) == wanted
+            })
+            .ok_or_else(|| UnknownMeasureType(s.to_string()))
+    }
+}
+
+/// Serializes as the type id used by the API.
+impl Serialize for MeasureType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the type id used by the API, keeping unknown ids.
+impl<'de> Deserialize<'de> for MeasureType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(MeasureType::from_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_type_round_trips() {
+        let mut ids = HashSet::new();
+        for measure_type in MeasureType::ALL {
+            let id = measure_type.id();
+            assert!(ids.insert(id), "{:?} shares its id", measure_type);
+            assert_eq!(MeasureType::from_id(id), measure_type);
+            assert_eq!(MeasureType::try_from(id), Ok(measure_type));
+            assert_eq!(id.to_string().parse(), Ok(measure_type));
+            assert_eq!(measure_type.name().parse(), Ok(measure_type));
+            assert_eq!(format!("{:?}", measure_type).parse(), Ok(measure_type));
+            assert!(!measure_type.is_unknown());
+        }
+    }
+
+    #[test]
+    fn unknown_ids_are_rejected_by_parsing_and_try_from() {
+        let error = UnknownMeasureType("9999".to_string());
+        assert_eq!("9999".parse::<MeasureType>(), Err(error.clone()));
+        assert_eq!(MeasureType::try_from(9999), Err(error));
+        assert_eq!(
+            "no such type".parse::<MeasureType>(),
+            Err(UnknownMeasureType("no such type".to_string()))
+        );
+    }
+
+    #[test]
+    fn unknown_type_is_kept_through_serde() {
+        let measure_type: MeasureType = serde_json::from_value(json!(9999)).unwrap();
+        assert_eq!(measure_type, MeasureType::Unknown(9999));
+        assert_eq!(serde_json::to_value(measure_type).unwrap(), json!(9999));
+        assert_eq!(measure_type.unit(), Unit::Unknown);
+    }
+}
@@ -1,12 +1,40 @@
 //! # Module: models
 //! Used to export the JSON models returned from the API
 
+pub mod activity;
+pub use self::activity::Activity;
 pub mod auth;
+pub mod date;
+pub mod datetime;
+pub(crate) mod de;
 pub use self::auth::Config;
 pub use self::auth::OauthResponse;
+pub mod device;
+pub use self::device::Device;
+pub mod goals;
+pub use self::goals::Goals;
+pub mod heart;
 pub mod meas;
+pub use self::meas::Attribution;
 pub use self::meas::Body;
+pub use self::meas::BodyPosition;
 pub use self::meas::Measure;
 pub use self::meas::MeasureType;
 pub use self::meas::Measuregrp;
 pub use self::meas::ResponseMeas;
+pub mod measure_type;
+pub use self::measure_type::MeasureCategory;
+pub mod notify;
+pub mod readings;
+pub use self::readings::BloodPressureReading;
+pub use self::readings::BodyComposition;
+pub use self::readings::SegmentalComposition;
+pub mod sleep;
+pub use self::sleep::SleepState;
+pub mod stetho;
+pub mod unit;
+pub use self::unit::Quantity;
+pub use self::unit::Unit;
+pub use self::unit::UnitPreferences;
+pub mod workout;
+pub use self::workout::Workout;
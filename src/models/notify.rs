@@ -0,0 +1,125 @@
+//! # Notify model
+//! Docs: https://developer.withings.com/api-reference/#tag/notify
+//! Response bodies from the notify v2 endpoints, which manage the callback URLs Withings calls
+//! when new data is available.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// The body of a notify list response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionsBody {
+    pub profiles: Vec<Subscription>,
+}
+
+/// A notification subscription: Withings calls `callbackurl` when data of `appli` changes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub appli: Appli,
+    pub callbackurl: String,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// When the subscription expires, as epoch seconds
+    #[serde(default)]
+    pub expires: Option<i64>,
+}
+
+/// The kind of data a subscription notifies about, from the `appli` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Appli {
+    /// Weight and body composition
+    Weight,
+    /// Temperature
+    Temperature,
+    /// Blood pressure, heart rate and SpO2
+    Pressure,
+    /// Activity
+    Activity,
+    /// Sleep
+    Sleep,
+    /// User actions, e.g. a device linked or unlinked
+    UserActions,
+    /// The user got in bed
+    BedIn,
+    /// The user got out of bed
+    BedOut,
+    /// A sleep sensor finished inflating
+    InflateDone,
+    /// A device was set up without a linked account
+    NoAccountAssociated,
+    /// A new ECG recording
+    Ecg,
+    /// An ECG recording failed
+    EcgFailed,
+    /// Glucose
+    Glucose,
+    /// Heart rate variability
+    Hrv,
+    /// An `appli` value this library does not know
+    Unknown(i64),
+}
+
+impl Appli {
+    /// Returns the `appli` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            Appli::Weight => 1,
+            Appli::Temperature => 2,
+            Appli::Pressure => 4,
+            Appli::Activity => 16,
+            Appli::Sleep => 44,
+            Appli::UserActions => 46,
+            Appli::BedIn => 50,
+            Appli::BedOut => 51,
+            Appli::InflateDone => 52,
+            Appli::NoAccountAssociated => 53,
+            Appli::Ecg => 54,
+            Appli::EcgFailed => 55,
+            Appli::Glucose => 58,
+            Appli::Hrv => 62,
+            Appli::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `Appli` for an `appli` value, `Unknown` if the value is not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            1 => Appli::Weight,
+            2 => Appli::Temperature,
+            4 => Appli::Pressure,
+            16 => Appli::Activity,
+            44 => Appli::Sleep,
+            46 => Appli::UserActions,
+            50 => Appli::BedIn,
+            51 => Appli::BedOut,
+            52 => Appli::InflateDone,
+            53 => Appli::NoAccountAssociated,
+            54 => Appli::Ecg,
+            55 => Appli::EcgFailed,
+            58 => Appli::Glucose,
+            62 => Appli::Hrv,
+            id => Appli::Unknown(id),
+        }
+    }
+}
+
+/// Displays the `appli` value used by the API.
+impl fmt::Display for Appli {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+/// Serializes as the `appli` value used by the API.
+impl Serialize for Appli {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `appli` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for Appli {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(Appli::from_id)
+    }
+}
@@ -0,0 +1,331 @@
+//! # Readings
+//! Typed views over a single measure group. A Body+ or Body Cardio weigh-in arrives as one
+//! `Measuregrp` holding weight, fat ratio, muscle mass and so on, and a blood pressure reading as
+//! one group holding systolic, diastolic and pulse. These views pick the measures out by type and
+//! decode them, so callers do not have to deal with raw type ids.
+//!
+//! Segmental scales also send fat free mass, fat mass and muscle mass per body segment, told
+//! apart by the `position` of each measure. `SegmentalComposition` breaks those down per segment.
+
+use crate::models::meas::{BodyPosition, MeasureType, Measuregrp};
+use crate::models::unit::{Quantity, UnitPreferences, WithUnits};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Body composition from a scale weigh-in. Fields are `None` when the scale did not measure them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BodyComposition {
+    pub grpid: i64,
+    /// When the weigh-in was taken, as epoch seconds
+    pub date: i64,
+    pub weight: Option<Quantity>,
+    pub fat_ratio: Option<Quantity>,
+    pub fat_mass: Option<Quantity>,
+    pub fat_free_mass: Option<Quantity>,
+    pub muscle_mass: Option<Quantity>,
+    pub bone_mass: Option<Quantity>,
+    pub hydration: Option<Quantity>,
+    pub visceral_fat: Option<Quantity>,
+    pub extracellular_water: Option<Quantity>,
+    pub intracellular_water: Option<Quantity>,
+    pub basal_metabolic_rate: Option<Quantity>,
+    pub metabolic_age: Option<Quantity>,
+    pub heart_pulse: Option<Quantity>,
+    pub pulse_wave_velocity: Option<Quantity>,
+}
+
+impl BodyComposition {
+    /// Returns `true` if the group held none of the body composition measures.
+    pub fn is_empty(&self) -> bool {
+        self.weight.is_none()
+            && self.fat_ratio.is_none()
+            && self.fat_mass.is_none()
+            && self.fat_free_mass.is_none()
+            && self.muscle_mass.is_none()
+            && self.bone_mass.is_none()
+            && self.hydration.is_none()
+            && self.visceral_fat.is_none()
+            && self.extracellular_water.is_none()
+            && self.intracellular_water.is_none()
+            && self.basal_metabolic_rate.is_none()
+            && self.metabolic_age.is_none()
+    }
+
+    /// Returns the measures that were taken, with their labels, in display order.
+    fn labeled(&self) -> impl Iterator<Item = (&'static str, &Quantity)> {
+        [
+            ("weight", &self.weight),
+            ("fat ratio", &self.fat_ratio),
+            ("fat mass", &self.fat_mass),
+            ("fat free mass", &self.fat_free_mass),
+            ("muscle mass", &self.muscle_mass),
+            ("bone mass", &self.bone_mass),
+            ("hydration", &self.hydration),
+            ("visceral fat", &self.visceral_fat),
+            ("extracellular water", &self.extracellular_water),
+            ("intracellular water", &self.intracellular_water),
+            ("basal metabolic rate", &self.basal_metabolic_rate),
+            ("metabolic age", &self.metabolic_age),
+            ("heart pulse", &self.heart_pulse),
+            ("pulse wave velocity", &self.pulse_wave_velocity),
+        ]
+        .into_iter()
+        .filter_map(|(label, quantity)| Some((label, quantity.as_ref()?)))
+    }
+
+    /// Returns a `Display` adapter that shows the measures in the units chosen by `preferences`.
+    pub fn display_with<'a>(&'a self, preferences: &'a UnitPreferences) -> WithUnits<'a, Self> {
+        WithUnits {
+            value: self,
+            preferences,
+        }
+    }
+}
+
+/// Displays the measures that were taken, e.g. `weight 72.35 kg, fat ratio 21.54 %`.
+impl fmt::Display for BodyComposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_with(&UnitPreferences::default()))
+    }
+}
+
+/// Displays the measures that were taken in the preferred units.
+impl fmt::Display for WithUnits<'_, BodyComposition> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (label, quantity)) in self.value.labeled().enumerate() {
+            let separator = if i == 0 { "" } else { ", " };
+            write!(
+                f,
+                "{}{} {}",
+                separator,
+                label,
+                quantity.convert(self.preferences)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&Measuregrp> for BodyComposition {
+    fn from(group: &Measuregrp) -> Self {
+        let quantity = |measure_type| group.measure(measure_type).map(|m| m.quantity());
+        BodyComposition {
+            grpid: group.grpid(),
+            date: group.date(),
+            weight: quantity(MeasureType::Weight),
+            fat_ratio: quantity(MeasureType::FatRatio),
+            fat_mass: quantity(MeasureType::FatMassWeight),
+            fat_free_mass: quantity(MeasureType::FatFreeMass),
+            muscle_mass: quantity(MeasureType::MuscleMass),
+            bone_mass: quantity(MeasureType::BoneMass),
+            hydration: quantity(MeasureType::Hydration),
+            visceral_fat: quantity(MeasureType::VisceralFatMass),
+            extracellular_water: quantity(MeasureType::ExtracellularWater),
+            intracellular_water: quantity(MeasureType::IntracellularWater),
+            basal_metabolic_rate: quantity(MeasureType::BasalMetabolicRate),
+            metabolic_age: quantity(MeasureType::MetabolicAge),
+            heart_pulse: quantity(MeasureType::HeartPulse),
+            pulse_wave_velocity: quantity(MeasureType::PulseWaveVelocity),
+        }
+    }
+}
+
+/// A blood pressure reading from a blood pressure monitor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BloodPressureReading {
+    pub grpid: i64,
+    /// When the reading was taken, as epoch seconds
+    pub date: i64,
+    pub systolic: Quantity,
+    pub diastolic: Quantity,
+    pub heart_pulse: Option<Quantity>,
+}
+
+impl BloodPressureReading {
+    /// Builds the reading from a measure group, `None` if the group has no systolic or no
+    /// diastolic measure.
+    pub fn from_group(group: &Measuregrp) -> Option<Self> {
+        let quantity = |measure_type| group.measure(measure_type).map(|m| m.quantity());
+        Some(BloodPressureReading {
+            grpid: group.grpid(),
+            date: group.date(),
+            systolic: quantity(MeasureType::SystolicBloodPressure)?,
+            diastolic: quantity(MeasureType::DiastolicBloodPressure)?,
+            heart_pulse: quantity(MeasureType::HeartPulse),
+        })
+    }
+
+    /// Returns a `Display` adapter that shows the reading in the units chosen by `preferences`.
+    pub fn display_with<'a>(&'a self, preferences: &'a UnitPreferences) -> WithUnits<'a, Self> {
+        WithUnits {
+            value: self,
+            preferences,
+        }
+    }
+}
+
+/// Displays the reading, e.g. `systolic 121 mmHg, diastolic 79 mmHg, heart pulse 62 bpm`.
+impl fmt::Display for BloodPressureReading {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_with(&UnitPreferences::default()))
+    }
+}
+
+/// Displays the reading in the preferred units.
+impl fmt::Display for WithUnits<'_, BloodPressureReading> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reading = self.value;
+        write!(
+            f,
+            "systolic {}, diastolic {}",
+            reading.systolic.convert(self.preferences),
+            reading.diastolic.convert(self.preferences)
+        )?;
+        if let Some(heart_pulse) = &reading.heart_pulse {
+            write!(f, ", heart pulse {}", heart_pulse.convert(self.preferences))?;
+        }
+        Ok(())
+    }
+}
+
+/// The measures of one body segment. Fields are `None` when the scale did not measure them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SegmentMeasures {
+    pub fat_free_mass: Option<Quantity>,
+    pub fat_mass: Option<Quantity>,
+    pub muscle_mass: Option<Quantity>,
+}
+
+/// Per segment body composition from a segmental scale weigh-in.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SegmentalComposition {
+    pub grpid: i64,
+    /// When the weigh-in was taken, as epoch seconds
+    pub date: i64,
+    pub segments: BTreeMap<BodyPosition, SegmentMeasures>,
+}
+
+impl SegmentalComposition {
+    /// Returns the measures of a segment.
+    pub fn segment(&self, position: BodyPosition) -> Option<&SegmentMeasures> {
+        self.segments.get(&position)
+    }
+}
+
+impl From<&Measuregrp> for SegmentalComposition {
+    fn from(group: &Measuregrp) -> Self {
+        let mut segments: BTreeMap<BodyPosition, SegmentMeasures> = BTreeMap::new();
+        for measure in &group.measures {
+            let Some(position) = measure.position else {
+                continue;
+            };
+            if !matches!(
+                measure.measure_type,
+                MeasureType::FatFreeMassSegments
+                    | MeasureType::FatMass
+                    | MeasureType::MuscleMassSegments
+            ) {
+                continue;
+            }
+            let segment = segments.entry(position).or_default();
+            let field = match measure.measure_type {
+                MeasureType::FatFreeMassSegments => &mut segment.fat_free_mass,
+                MeasureType::FatMass => &mut segment.fat_mass,
+                _ => &mut segment.muscle_mass,
+            };
+            *field = Some(measure.quantity());
+        }
+
+        SegmentalComposition {
+            grpid: group.grpid(),
+            date: group.date(),
+            segments,
+        }
+    }
+}
+
+impl Measuregrp {
+    /// Returns the body composition view of the group, `None` if it has no body composition
+    /// measures.
+    pub fn body_composition(&self) -> Option<BodyComposition> {
+        Some(BodyComposition::from(self)).filter(|composition| !composition.is_empty())
+    }
+
+    /// Returns the per segment view of the group, `None` if it has no segmental measures.
+    pub fn segmental_composition(&self) -> Option<SegmentalComposition> {
+        Some(SegmentalComposition::from(self))
+            .filter(|composition| !composition.segments.is_empty())
+    }
+
+    /// Returns the blood pressure view of the group, `None` if it is not a blood pressure
+    /// reading.
+    pub fn blood_pressure(&self) -> Option<BloodPressureReading> {
+        BloodPressureReading::from_group(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::unit::{MassUnit, PressureUnit};
+
+    fn group(measures: serde_json::Value) -> Measuregrp {
+        serde_json::from_value(serde_json::json!({
+            "grpid": 1,
+            "attrib": 0,
+            "date": 1706083263,
+            "created": 1706083263,
+            "modified": 1706083263,
+            "category": 1,
+            "measures": measures,
+            "comment": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn body_composition_displays_in_the_preferred_units() {
+        let group = group(serde_json::json!([
+            { "value": 63500, "type": 1, "unit": -3, "algo": 0, "fm": 0 },
+            { "value": 2154, "type": 6, "unit": -2, "algo": 0, "fm": 0 },
+            { "value": 3100, "type": 88, "unit": -3, "algo": 0, "fm": 0 }
+        ]));
+        let composition = group.body_composition().unwrap();
+        assert_eq!(
+            composition.to_string(),
+            "weight 63.5 kg, fat ratio 21.54 %, bone mass 3.1 kg"
+        );
+
+        let stone = UnitPreferences {
+            mass: MassUnit::Stone,
+            ..UnitPreferences::default()
+        };
+        assert_eq!(
+            composition.display_with(&stone).to_string(),
+            "weight 10 st 0.0 lb, fat ratio 21.54 %, bone mass 0 st 6.8 lb"
+        );
+    }
+
+    #[test]
+    fn blood_pressure_displays_in_the_preferred_units() {
+        let group = group(serde_json::json!([
+            { "value": 120, "type": 10, "unit": 0, "algo": 0, "fm": 0 },
+            { "value": 80, "type": 9, "unit": 0, "algo": 0, "fm": 0 },
+            { "value": 62, "type": 11, "unit": 0, "algo": 0, "fm": 0 }
+        ]));
+        let reading = group.blood_pressure().unwrap();
+        assert_eq!(
+            reading.to_string(),
+            "systolic 120 mmHg, diastolic 80 mmHg, heart pulse 62 bpm"
+        );
+
+        let kpa = UnitPreferences {
+            pressure: PressureUnit::Kilopascal,
+            ..UnitPreferences::default()
+        };
+        assert_eq!(
+            reading.display_with(&kpa).to_string(),
+            "systolic 16.00 kPa, diastolic 10.67 kPa, heart pulse 62 bpm"
+        );
+    }
+}
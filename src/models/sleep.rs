@@ -0,0 +1,442 @@
+//! # Sleep model
+//! Docs: https://developer.withings.com/api-reference/#tag/sleep/operation/sleepv2-get
+//! Docs: https://developer.withings.com/api-reference/#tag/sleep/operation/sleepv2-getsummary
+//! Response bodies from the sleep v2 get endpoint, the sleep states of a night with the high
+//! frequency series recorded by the sleep analyzer or tracker, and the sleep v2 getsummary
+//! endpoint, one summary per night
+
+use crate::models::date::Date;
+use crate::models::de::{self, seconds};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Response from the API is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseSleep {
+    pub status: i64,
+    pub body: SleepBody,
+}
+
+/// The body of the response is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepBody {
+    pub series: Vec<SleepSegment>,
+}
+
+/// A stretch of time spent in one sleep state, with the series recorded during it. Series not
+/// requested with `data_fields`, or not recorded by the device, are empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepSegment {
+    /// Start of the segment, as epoch seconds
+    pub startdate: i64,
+    /// End of the segment, as epoch seconds
+    pub enddate: i64,
+    pub state: SleepState,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub model_id: Option<i64>,
+    #[serde(default)]
+    pub hash_deviceid: Option<String>,
+    /// Heart rate in beats per minute, keyed by epoch seconds
+    #[serde(default, deserialize_with = "de::timestamp_map")]
+    pub hr: BTreeMap<i64, i64>,
+    /// Respiration rate in breaths per minute, keyed by epoch seconds
+    #[serde(default, deserialize_with = "de::timestamp_map")]
+    pub rr: BTreeMap<i64, i64>,
+    /// Seconds of snoring, keyed by epoch seconds
+    #[serde(default, deserialize_with = "de::timestamp_map")]
+    pub snoring: BTreeMap<i64, i64>,
+    /// Heart rate variability, standard deviation of the NN intervals in milliseconds, keyed by
+    /// epoch seconds
+    #[serde(default, deserialize_with = "de::timestamp_map")]
+    pub sdnn_1: BTreeMap<i64, f64>,
+}
+
+/// The sleep state of a segment, from the `state` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SleepState {
+    Awake,
+    Light,
+    Deep,
+    Rem,
+    /// Entered manually by the user
+    Manual,
+    /// Recorded without a known state
+    Unspecified,
+    /// A `state` value this library does not know
+    Unknown(i64),
+}
+
+impl SleepState {
+    /// Returns the `state` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            SleepState::Awake => 0,
+            SleepState::Light => 1,
+            SleepState::Deep => 2,
+            SleepState::Rem => 3,
+            SleepState::Manual => 4,
+            SleepState::Unspecified => 5,
+            SleepState::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `SleepState` for a `state` value, `Unknown` if the value is not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            0 => SleepState::Awake,
+            1 => SleepState::Light,
+            2 => SleepState::Deep,
+            3 => SleepState::Rem,
+            4 => SleepState::Manual,
+            5 => SleepState::Unspecified,
+            id => SleepState::Unknown(id),
+        }
+    }
+
+    /// Returns `true` for the light, deep and REM states.
+    pub fn is_asleep(&self) -> bool {
+        matches!(self, SleepState::Light | SleepState::Deep | SleepState::Rem)
+    }
+}
+
+/// Serializes as the `state` value used by the API.
+impl Serialize for SleepState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `state` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for SleepState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(SleepState::from_id)
+    }
+}
+
+/// The series of a sleep segment that can be requested with `data_fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SleepField {
+    Hr,
+    Rr,
+    Snoring,
+    Sdnn1,
+}
+
+impl SleepField {
+    /// Returns the name of the field in the API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SleepField::Hr => "hr",
+            SleepField::Rr => "rr",
+            SleepField::Snoring => "snoring",
+            SleepField::Sdnn1 => "sdnn_1",
+        }
+    }
+}
+
+/// The sleep states and series of a range of time, merged across requests.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SleepTimeline {
+    /// The sleep states, as `(startdate, enddate, state)` in chronological order
+    pub states: Vec<(i64, i64, SleepState)>,
+    /// Heart rate in beats per minute, keyed by epoch seconds
+    pub hr: BTreeMap<i64, i64>,
+    /// Respiration rate in breaths per minute, keyed by epoch seconds
+    pub rr: BTreeMap<i64, i64>,
+    /// Seconds of snoring, keyed by epoch seconds
+    pub snoring: BTreeMap<i64, i64>,
+    /// Heart rate variability in milliseconds, keyed by epoch seconds
+    pub sdnn_1: BTreeMap<i64, f64>,
+}
+
+impl SleepTimeline {
+    /// Adds the states and series of segments to the timeline. A segment returned again by an
+    /// overlapping request is only kept once.
+    pub fn extend(&mut self, segments: impl IntoIterator<Item = SleepSegment>) {
+        for segment in segments {
+            self.states
+                .push((segment.startdate, segment.enddate, segment.state));
+            self.hr.extend(segment.hr);
+            self.rr.extend(segment.rr);
+            self.snoring.extend(segment.snoring);
+            self.sdnn_1.extend(segment.sdnn_1);
+        }
+        self.states.sort_by_key(|(start, end, _)| (*start, *end));
+        self.states.dedup();
+    }
+
+    /// Returns the time spent in `state`.
+    pub fn time_in(&self, state: SleepState) -> Duration {
+        self.states
+            .iter()
+            .filter(|(_, _, s)| *s == state)
+            .filter_map(|(start, end, _)| seconds(Some(end - start)))
+            .sum()
+    }
+}
+
+/// Response from the sleep v2 getsummary endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseSleepSummary {
+    pub status: i64,
+    pub body: SleepSummaryBody,
+}
+
+/// The body of a sleep summary response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepSummaryBody {
+    pub series: Vec<SleepSummary>,
+    /// Whether more summaries are available past this page
+    #[serde(default, deserialize_with = "de::bool_or_int")]
+    pub more: bool,
+    /// Offset to request the next page with, set when `more` is true
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+/// The summary of one night of sleep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SleepSummary {
+    #[serde(default)]
+    pub id: Option<i64>,
+    /// IANA timezone of the user during the night
+    pub timezone: String,
+    /// Model id of the device that recorded the night
+    #[serde(default)]
+    pub model: Option<i64>,
+    #[serde(default)]
+    pub model_id: Option<i64>,
+    #[serde(default)]
+    pub hash_deviceid: Option<String>,
+    /// Start of the night, as epoch seconds
+    pub startdate: i64,
+    /// End of the night, as epoch seconds
+    pub enddate: i64,
+    /// The day the night ended, in the timezone of the user
+    pub date: Date,
+    #[serde(default)]
+    pub created: Option<i64>,
+    #[serde(default)]
+    pub modified: Option<i64>,
+    #[serde(default)]
+    pub data: SleepSummaryData,
+}
+
+impl SleepSummary {
+    /// Returns the time spent in each sleep stage, as `(light, deep, rem)`.
+    pub fn stage_durations(&self) -> (Option<Duration>, Option<Duration>, Option<Duration>) {
+        (
+            seconds(self.data.lightsleepduration),
+            seconds(self.data.deepsleepduration),
+            seconds(self.data.remsleepduration),
+        )
+    }
+}
+
+/// The data of a night. Fields the device did not record, or not requested with `data_fields`,
+/// are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SleepSummaryData {
+    /// Apnea and hypopnea events per hour
+    #[serde(default)]
+    pub apnea_hypopnea_index: Option<f64>,
+    /// Seconds asleep
+    #[serde(default)]
+    pub asleepduration: Option<i64>,
+    /// Intensity of the breathing disturbances
+    #[serde(default)]
+    pub breathing_disturbances_intensity: Option<i64>,
+    /// Seconds of deep sleep
+    #[serde(default)]
+    pub deepsleepduration: Option<i64>,
+    /// Seconds to fall asleep
+    #[serde(default)]
+    pub durationtosleep: Option<i64>,
+    /// Seconds to get up after waking up
+    #[serde(default)]
+    pub durationtowakeup: Option<i64>,
+    /// Average heart rate in beats per minute
+    #[serde(default)]
+    pub hr_average: Option<i64>,
+    #[serde(default)]
+    pub hr_max: Option<i64>,
+    #[serde(default)]
+    pub hr_min: Option<i64>,
+    /// Seconds of light sleep
+    #[serde(default)]
+    pub lightsleepduration: Option<i64>,
+    /// Number of REM sleep phases
+    #[serde(default)]
+    pub nb_rem_episodes: Option<i64>,
+    /// Times the user got out of bed
+    #[serde(default)]
+    pub out_of_bed_count: Option<i64>,
+    /// Seconds of REM sleep
+    #[serde(default)]
+    pub remsleepduration: Option<i64>,
+    /// Average respiration rate in breaths per minute
+    #[serde(default)]
+    pub rr_average: Option<i64>,
+    #[serde(default)]
+    pub rr_max: Option<i64>,
+    #[serde(default)]
+    pub rr_min: Option<i64>,
+    /// Ratio of the time asleep to the time in bed, from 0 to 1
+    #[serde(default)]
+    pub sleep_efficiency: Option<f64>,
+    /// Seconds to fall asleep
+    #[serde(default)]
+    pub sleep_latency: Option<i64>,
+    /// Sleep score from 0 to 100
+    #[serde(default)]
+    pub sleep_score: Option<i64>,
+    /// Seconds of snoring
+    #[serde(default)]
+    pub snoring: Option<i64>,
+    /// Number of snoring episodes
+    #[serde(default)]
+    pub snoringepisodecount: Option<i64>,
+    /// Seconds of light, deep and REM sleep
+    #[serde(default)]
+    pub total_sleep_time: Option<i64>,
+    /// Seconds in bed
+    #[serde(default)]
+    pub total_timeinbed: Option<i64>,
+    /// Seconds between waking up and getting up
+    #[serde(default)]
+    pub wakeup_latency: Option<i64>,
+    /// Number of times the user woke up
+    #[serde(default)]
+    pub wakeupcount: Option<i64>,
+    /// Seconds awake
+    #[serde(default)]
+    pub wakeupduration: Option<i64>,
+    /// Seconds awake after falling asleep
+    #[serde(default)]
+    pub waso: Option<i64>,
+}
+
+/// The fields of the summary data that can be requested with `data_fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SleepSummaryField {
+    ApneaHypopneaIndex,
+    Asleepduration,
+    BreathingDisturbancesIntensity,
+    Deepsleepduration,
+    Durationtosleep,
+    Durationtowakeup,
+    HrAverage,
+    HrMax,
+    HrMin,
+    Lightsleepduration,
+    NbRemEpisodes,
+    OutOfBedCount,
+    Remsleepduration,
+    RrAverage,
+    RrMax,
+    RrMin,
+    SleepEfficiency,
+    SleepLatency,
+    SleepScore,
+    Snoring,
+    Snoringepisodecount,
+    TotalSleepTime,
+    TotalTimeinbed,
+    WakeupLatency,
+    Wakeupcount,
+    Wakeupduration,
+    Waso,
+}
+
+impl SleepSummaryField {
+    /// Returns the name of the field in the API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SleepSummaryField::ApneaHypopneaIndex => "apnea_hypopnea_index",
+            SleepSummaryField::Asleepduration => "asleepduration",
+            SleepSummaryField::BreathingDisturbancesIntensity => "breathing_disturbances_intensity",
+            SleepSummaryField::Deepsleepduration => "deepsleepduration",
+            SleepSummaryField::Durationtosleep => "durationtosleep",
+            SleepSummaryField::Durationtowakeup => "durationtowakeup",
+            SleepSummaryField::HrAverage => "hr_average",
+            SleepSummaryField::HrMax => "hr_max",
+            SleepSummaryField::HrMin => "hr_min",
+            SleepSummaryField::Lightsleepduration => "lightsleepduration",
+            SleepSummaryField::NbRemEpisodes => "nb_rem_episodes",
+            SleepSummaryField::OutOfBedCount => "out_of_bed_count",
+            SleepSummaryField::Remsleepduration => "remsleepduration",
+            SleepSummaryField::RrAverage => "rr_average",
+            SleepSummaryField::RrMax => "rr_max",
+            SleepSummaryField::RrMin => "rr_min",
+            SleepSummaryField::SleepEfficiency => "sleep_efficiency",
+            SleepSummaryField::SleepLatency => "sleep_latency",
+            SleepSummaryField::SleepScore => "sleep_score",
+            SleepSummaryField::Snoring => "snoring",
+            SleepSummaryField::Snoringepisodecount => "snoringepisodecount",
+            SleepSummaryField::TotalSleepTime => "total_sleep_time",
+            SleepSummaryField::TotalTimeinbed => "total_timeinbed",
+            SleepSummaryField::WakeupLatency => "wakeup_latency",
+            SleepSummaryField::Wakeupcount => "wakeupcount",
+            SleepSummaryField::Wakeupduration => "wakeupduration",
+            SleepSummaryField::Waso => "waso",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn segment(startdate: i64, enddate: i64, state: SleepState) -> SleepSegment {
+        serde_json::from_value(json!({
+            "startdate": startdate,
+            "enddate": enddate,
+            "state": state.id(),
+            "hr": { startdate.to_string(): 60 },
+            "rr": []
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn extend_keeps_a_segment_returned_by_two_windows_once() {
+        let mut timeline = SleepTimeline::default();
+        // The deep segment crosses the window boundary at 200, so both windows return it
+        timeline.extend([
+            segment(100, 180, SleepState::Light),
+            segment(180, 260, SleepState::Deep),
+        ]);
+        timeline.extend([
+            segment(180, 260, SleepState::Deep),
+            segment(260, 300, SleepState::Rem),
+        ]);
+
+        assert_eq!(
+            timeline.states,
+            vec![
+                (100, 180, SleepState::Light),
+                (180, 260, SleepState::Deep),
+                (260, 300, SleepState::Rem),
+            ]
+        );
+        assert_eq!(
+            timeline.hr.keys().copied().collect::<Vec<_>>(),
+            [100, 180, 260]
+        );
+        assert!(timeline.rr.is_empty());
+        assert_eq!(timeline.time_in(SleepState::Deep), Duration::from_secs(80));
+        assert_eq!(timeline.time_in(SleepState::Awake), Duration::ZERO);
+    }
+
+    #[test]
+    fn sleep_state_keeps_unknown_values() {
+        assert_eq!(SleepState::from_id(SleepState::Rem.id()), SleepState::Rem);
+        let state: SleepState = serde_json::from_value(json!(9)).unwrap();
+        assert_eq!(state, SleepState::Unknown(9));
+        assert_eq!(serde_json::to_value(state).unwrap(), json!(9));
+    }
+}
@@ -0,0 +1,240 @@
+//! # Stetho model
+//! Docs: https://developer.withings.com/api-reference/#tag/stetho
+//! Response bodies from the stetho v2 list endpoint, the heart sound recordings of BPM Core
+//! devices, and the stetho v2 get endpoint, the signal of one recording
+
+use crate::models::de;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::Duration;
+
+/// Response from the stetho v2 list endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseStethoList {
+    pub status: i64,
+    pub body: StethoListBody,
+}
+
+/// The body of a stetho list response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StethoListBody {
+    pub series: Vec<StethoRecording>,
+    /// Whether more recordings are available past this page
+    #[serde(default, deserialize_with = "de::bool_or_int")]
+    pub more: bool,
+    /// Offset to request the next page with, set when `more` is true
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+/// A heart sound recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StethoRecording {
+    #[serde(default)]
+    pub deviceid: Option<String>,
+    /// Model id of the device that made the recording
+    #[serde(default)]
+    pub model: Option<i64>,
+    /// Id of the signal, to get it with `stetho::get_signal`
+    pub signalid: i64,
+    /// When the recording was made, as epoch seconds
+    pub timestamp: i64,
+    pub vhd: VhdClassification,
+    /// IANA timezone of the user
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
+
+/// Valvular heart disease classification of a recording, from the `vhd` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VhdClassification {
+    /// No sign of valvular heart disease
+    Negative,
+    /// Signs of valvular heart disease
+    Positive,
+    /// The recording could not be classified
+    Inconclusive,
+    /// A `vhd` value this library does not know
+    Unknown(i64),
+}
+
+impl VhdClassification {
+    /// Returns the `vhd` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            VhdClassification::Negative => 0,
+            VhdClassification::Positive => 1,
+            VhdClassification::Inconclusive => 2,
+            VhdClassification::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `VhdClassification` for a `vhd` value, `Unknown` if the value is not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            0 => VhdClassification::Negative,
+            1 => VhdClassification::Positive,
+            2 => VhdClassification::Inconclusive,
+            id => VhdClassification::Unknown(id),
+        }
+    }
+}
+
+/// Serializes as the `vhd` value used by the API.
+impl Serialize for VhdClassification {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `vhd` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for VhdClassification {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(VhdClassification::from_id)
+    }
+}
+
+/// Where the stethoscope was placed on the chest, from the `stethoscope_position` field
+///
+/// The ids follow the order of the four auscultation areas of the BPM Core guided exam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StethoPosition {
+    /// Aortic area, right of the upper sternum
+    Aortic,
+    /// Pulmonary area, left of the upper sternum
+    Pulmonary,
+    /// Tricuspid area, left of the lower sternum
+    Tricuspid,
+    /// Mitral area, at the apex of the heart
+    Mitral,
+    /// A `stethoscope_position` value this library does not know
+    Unknown(i64),
+}
+
+impl StethoPosition {
+    /// Returns the `stethoscope_position` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            StethoPosition::Aortic => 0,
+            StethoPosition::Pulmonary => 1,
+            StethoPosition::Tricuspid => 2,
+            StethoPosition::Mitral => 3,
+            StethoPosition::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `StethoPosition` for a `stethoscope_position` value, `Unknown` if the value is
+    /// not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            0 => StethoPosition::Aortic,
+            1 => StethoPosition::Pulmonary,
+            2 => StethoPosition::Tricuspid,
+            3 => StethoPosition::Mitral,
+            id => StethoPosition::Unknown(id),
+        }
+    }
+}
+
+/// Serializes as the `stethoscope_position` value used by the API.
+impl Serialize for StethoPosition {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `stethoscope_position` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for StethoPosition {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(StethoPosition::from_id)
+    }
+}
+
+/// Response from the stetho v2 get endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseStethoSignal {
+    pub status: i64,
+    pub body: StethoSignal,
+}
+
+/// The raw signal of a heart sound recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StethoSignal {
+    /// Raw samples
+    pub signal: Vec<i64>,
+    /// Samples per second, in Hz
+    pub frequency: i64,
+    /// Length of the recording in seconds as reported by the API, in the `duration` field.
+    /// `duration()` computes it from the samples instead.
+    #[serde(default, rename = "duration")]
+    pub reported_duration: Option<i64>,
+    /// Format of the samples, e.g. the bit depth
+    #[serde(default)]
+    pub format: Option<i64>,
+    /// Number of samples
+    #[serde(default)]
+    pub size: Option<i64>,
+    /// Resolution of the samples
+    #[serde(default)]
+    pub resolution: Option<i64>,
+    /// Number of channels
+    #[serde(default)]
+    pub channel: Option<i64>,
+    /// Model id of the device that made the recording
+    #[serde(default)]
+    pub device_model: Option<i64>,
+    #[serde(default)]
+    pub vhd: Option<VhdClassification>,
+    /// Where the stethoscope was placed on the chest
+    #[serde(default)]
+    pub stethoscope_position: Option<StethoPosition>,
+}
+
+impl StethoSignal {
+    /// Returns the samples with their offset from the start of the recording.
+    pub fn samples(&self) -> impl Iterator<Item = (Duration, f64)> + '_ {
+        de::signal_samples(&self.signal, self.frequency)
+    }
+
+    /// Returns the length of the recording, computed from the samples.
+    pub fn duration(&self) -> Duration {
+        de::signal_duration(&self.signal, self.frequency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn signal() -> StethoSignal {
+        serde_json::from_value(json!({
+            "signal": [1, -2, 3, 4],
+            "frequency": 2,
+            "duration": 3,
+            "stethoscope_position": 3
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn samples_carry_their_offset() {
+        let samples: Vec<_> = signal().samples().collect();
+        assert_eq!(samples[0], (Duration::ZERO, 1.0));
+        assert_eq!(samples[3], (Duration::from_millis(1500), 4.0));
+    }
+
+    #[test]
+    fn duration_is_computed_from_the_samples() {
+        let signal = signal();
+        assert_eq!(signal.duration(), Duration::from_secs(2));
+        assert_eq!(signal.reported_duration, Some(3));
+    }
+
+    #[test]
+    fn position_keeps_unknown_values() {
+        assert_eq!(signal().stethoscope_position, Some(StethoPosition::Mitral));
+        let position: StethoPosition = serde_json::from_value(json!(9)).unwrap();
+        assert_eq!(position, StethoPosition::Unknown(9));
+        assert_eq!(serde_json::to_value(position).unwrap(), json!(9));
+    }
+}
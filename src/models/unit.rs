@@ -0,0 +1,580 @@
+//! # Units
+//! Withings sends every measure as an integer `value` and a power of ten `unit` exponent, e.g.
+//! `72350` and `-3` for 72.35 kg. `Quantity` keeps that exact representation together with the SI
+//! unit implied by the measure type, and converts to `f64` on demand.
+//!
+//! # Examples
+//!
+//! ```
+//! use withings_rs::models::{Quantity, Unit};
+//!
+//! let weight = Quantity { value: 72350, exponent: -3, unit: Unit::Kilogram };
+//! assert_eq!(weight.to_string(), "72.35 kg");
+//! assert_eq!(weight.as_f64(), 72.35);
+//! ```
+
+use std::fmt;
+
+/// The unit of a decoded measure value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Kilogram,
+    Meter,
+    Percent,
+    MillimeterOfMercury,
+    BeatsPerMinute,
+    Celsius,
+    MeterPerSecond,
+    MilliliterPerMinutePerKilogram,
+    Millisecond,
+    Year,
+    /// Kilocalories per day, for the basal metabolic rate
+    KilocaloriePerDay,
+    Microsiemens,
+    /// A dimensionless value, e.g. a 0/1 flag
+    Unitless,
+    /// The unit of a measure type this library does not know
+    Unknown,
+}
+
+impl Unit {
+    /// Returns the symbol of the unit, empty for unitless and unknown values.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Kilogram => "kg",
+            Unit::Meter => "m",
+            Unit::Percent => "%",
+            Unit::MillimeterOfMercury => "mmHg",
+            Unit::BeatsPerMinute => "bpm",
+            Unit::Celsius => "°C",
+            Unit::MeterPerSecond => "m/s",
+            Unit::MilliliterPerMinutePerKilogram => "mL/min/kg",
+            Unit::Millisecond => "ms",
+            Unit::Year => "years",
+            Unit::KilocaloriePerDay => "kcal/day",
+            Unit::Microsiemens => "µS",
+            Unit::Unitless | Unit::Unknown => "",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// An exact decimal value with its unit: `value × 10^exponent unit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quantity {
+    pub value: i64,
+    pub exponent: i64,
+    pub unit: Unit,
+}
+
+/// Largest exponent magnitude that still matters for an `f64`: any `i64` value scaled past it
+/// overflows to infinity or underflows to zero.
+const MAX_F64_EXPONENT: i64 = 400;
+/// Largest exponent magnitude written out in full by `to_decimal_string`, so an untrusted exponent
+/// cannot make it allocate without bound.
+const MAX_DECIMAL_EXPONENT: i64 = 40;
+
+impl Quantity {
+    /// Returns the decoded value as a float.
+    pub fn as_f64(&self) -> f64 {
+        let exponent = self.exponent.clamp(-MAX_F64_EXPONENT, MAX_F64_EXPONENT);
+        // Dividing by the exact power of ten rounds correctly, multiplying by 10^-n does not.
+        let scale = 10f64.powi(exponent.unsigned_abs() as i32);
+        if exponent < 0 {
+            self.value as f64 / scale
+        } else {
+            self.value as f64 * scale
+        }
+    }
+
+    /// Formats the value without rounding, dropping trailing zeros after the decimal point.
+    ///
+    /// Exponents beyond ±40, which no real measure uses, are written in scientific notation, e.g.
+    /// `72e-50`, instead of padding the value with zeros.
+    pub fn to_decimal_string(&self) -> String {
+        let digits = self.value.unsigned_abs().to_string();
+        let sign = if self.value < 0 { "-" } else { "" };
+
+        if self.value == 0 {
+            return "0".to_string();
+        }
+        if self.exponent.abs() > MAX_DECIMAL_EXPONENT {
+            return format!("{}{}e{}", sign, digits, self.exponent);
+        }
+
+        if self.exponent >= 0 {
+            let zeros = "0".repeat(self.exponent as usize);
+            return format!("{}{}{}", sign, digits, zeros);
+        }
+
+        let scale = self.exponent.unsigned_abs() as usize;
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int_part, frac_part) = digits.split_at(digits.len() - scale);
+        let frac_part = frac_part.trim_end_matches('0');
+        if frac_part.is_empty() {
+            format!("{}{}", sign, int_part)
+        } else {
+            format!("{}{}.{}", sign, int_part, frac_part)
+        }
+    }
+}
+
+/// Displays the exact value followed by the unit symbol, e.g. `72.35 kg`.
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.unit.symbol() {
+            "" => write!(f, "{}", self.to_decimal_string()),
+            symbol => write!(f, "{} {}", self.to_decimal_string(), symbol),
+        }
+    }
+}
+
+/// Pounds per kilogram, from the exact definition 1 lb = 0.45359237 kg
+const KG_PER_POUND: f64 = 0.453_592_37;
+const POUNDS_PER_STONE: f64 = 14.0;
+/// Meters per mile, from the exact definition 1 mi = 1609.344 m
+const METERS_PER_MILE: f64 = 1_609.344;
+/// Meters per inch, from the exact definition 1 in = 0.0254 m
+const METERS_PER_INCH: f64 = 0.0254;
+const INCHES_PER_FOOT: f64 = 12.0;
+/// Kilopascals per millimeter of mercury
+const KPA_PER_MMHG: f64 = 0.133_322_387_415;
+
+/// Unit to show masses in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MassUnit {
+    #[default]
+    Kilogram,
+    Pound,
+    /// Stone and pounds
+    Stone,
+}
+
+/// Unit to show temperatures in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+}
+
+/// Unit to show lengths, i.e. height, in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LengthUnit {
+    #[default]
+    Meter,
+    /// Feet and inches
+    FeetInches,
+}
+
+/// Unit to show blood pressures in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PressureUnit {
+    #[default]
+    MillimeterOfMercury,
+    Kilopascal,
+}
+
+/// Unit to show distances walked, run or cycled in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DistanceUnit {
+    #[default]
+    Meter,
+    Kilometer,
+    Mile,
+}
+
+/// The units a user wants to see decoded values in. Defaults to the units Withings sends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct UnitPreferences {
+    pub mass: MassUnit,
+    pub temperature: TemperatureUnit,
+    pub length: LengthUnit,
+    pub pressure: PressureUnit,
+    pub distance: DistanceUnit,
+}
+
+impl UnitPreferences {
+    /// Kilograms, degrees Celsius, meters, mmHg and kilometers for distances.
+    pub fn metric() -> Self {
+        UnitPreferences {
+            distance: DistanceUnit::Kilometer,
+            ..UnitPreferences::default()
+        }
+    }
+
+    /// Pounds, degrees Fahrenheit, feet and inches, mmHg and miles for distances.
+    pub fn imperial() -> Self {
+        UnitPreferences {
+            mass: MassUnit::Pound,
+            temperature: TemperatureUnit::Fahrenheit,
+            length: LengthUnit::FeetInches,
+            pressure: PressureUnit::MillimeterOfMercury,
+            distance: DistanceUnit::Mile,
+        }
+    }
+
+    /// Converts a distance in meters, as sent for activities and workouts, to the preferred
+    /// distance unit.
+    pub fn convert_distance(&self, meters: f64) -> Converted {
+        match self.distance {
+            DistanceUnit::Meter => Converted::Value {
+                value: meters,
+                symbol: "m",
+            },
+            DistanceUnit::Kilometer => Converted::Value {
+                value: meters / 1000.0,
+                symbol: "km",
+            },
+            DistanceUnit::Mile => Converted::Value {
+                value: meters / METERS_PER_MILE,
+                symbol: "mi",
+            },
+        }
+    }
+}
+
+/// A decoded value in the unit chosen by `UnitPreferences`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Converted {
+    /// The preferred unit is the unit Withings sent, the value is kept exact.
+    Exact(Quantity),
+    /// A value converted to another unit, with the symbol of that unit.
+    Value { value: f64, symbol: &'static str },
+    /// A mass in stone and pounds.
+    StonePounds { stone: i64, pounds: f64 },
+    /// A length in feet and inches.
+    FeetInches { feet: i64, inches: f64 },
+}
+
+impl Converted {
+    /// Returns the value as a single float in the preferred unit, e.g. total pounds for stone
+    /// and pounds, total inches for feet and inches.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Converted::Exact(quantity) => quantity.as_f64(),
+            Converted::Value { value, .. } => *value,
+            Converted::StonePounds { stone, pounds } => *stone as f64 * POUNDS_PER_STONE + pounds,
+            Converted::FeetInches { feet, inches } => *feet as f64 * INCHES_PER_FOOT + inches,
+        }
+    }
+}
+
+/// Displays the value with its unit. Converted values are shown with one decimal, two for kPa,
+/// kilometers and miles. Stone and pounds, and feet and inches, are rounded before they are split,
+/// so a value just below a whole stone or foot shows as `10 st 0.0 lb` rather than `9 st 14.0 lb`.
+impl fmt::Display for Converted {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Converted::Exact(quantity) => write!(f, "{}", quantity),
+            Converted::Value { value, symbol } if matches!(*symbol, "kPa" | "km" | "mi") => {
+                write!(f, "{:.2} {}", value, symbol)
+            }
+            Converted::Value { value, symbol } => write!(f, "{:.1} {}", value, symbol),
+            Converted::StonePounds { .. } => {
+                let (stone, pounds) = split_tenths(self.as_f64(), POUNDS_PER_STONE);
+                write!(f, "{} st {:.1} lb", stone, pounds)
+            }
+            Converted::FeetInches { .. } => {
+                let (feet, inches) = split_tenths(self.as_f64(), INCHES_PER_FOOT);
+                write!(f, "{} ft {:.1} in", feet, inches)
+            }
+        }
+    }
+}
+
+/// Rounds `total` to a tenth, then splits it into whole multiples of `per_unit` and the rest.
+fn split_tenths(total: f64, per_unit: f64) -> (i64, f64) {
+    let tenths = (total * 10.0).round() as i64;
+    let per_unit = (per_unit * 10.0) as i64;
+    (tenths / per_unit, (tenths % per_unit) as f64 / 10.0)
+}
+
+/// Formats a value in the units chosen by `UnitPreferences`, returned by the `display_with`
+/// methods of `Quantity`, `Measure` and the readings.
+pub struct WithUnits<'a, T: ?Sized> {
+    pub(crate) value: &'a T,
+    pub(crate) preferences: &'a UnitPreferences,
+}
+
+/// Displays the quantity converted to the preferred unit.
+impl fmt::Display for WithUnits<'_, Quantity> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value.convert(self.preferences))
+    }
+}
+
+impl Quantity {
+    /// Converts the value to the unit chosen by `preferences`.
+    ///
+    /// Values already in the preferred unit are returned exact. Other values are converted in a
+    /// single step from the exact decimal, so no rounding error accumulates, and the original
+    /// `Quantity` stays available for a lossless round trip.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use withings_rs::models::unit::{Quantity, Unit, UnitPreferences};
+    ///
+    /// let weight = Quantity { value: 72350, exponent: -3, unit: Unit::Kilogram };
+    /// assert_eq!(weight.convert(&UnitPreferences::metric()).to_string(), "72.35 kg");
+    /// assert_eq!(weight.convert(&UnitPreferences::imperial()).to_string(), "159.5 lb");
+    /// ```
+    pub fn convert(&self, preferences: &UnitPreferences) -> Converted {
+        let value = self.as_f64();
+        match (self.unit, preferences) {
+            (
+                Unit::Kilogram,
+                UnitPreferences {
+                    mass: MassUnit::Pound,
+                    ..
+                },
+            ) => Converted::Value {
+                value: value / KG_PER_POUND,
+                symbol: "lb",
+            },
+            (
+                Unit::Kilogram,
+                UnitPreferences {
+                    mass: MassUnit::Stone,
+                    ..
+                },
+            ) => {
+                let pounds = value / KG_PER_POUND;
+                let stone = (pounds / POUNDS_PER_STONE).trunc();
+                Converted::StonePounds {
+                    stone: stone as i64,
+                    pounds: pounds - stone * POUNDS_PER_STONE,
+                }
+            }
+            (
+                Unit::Celsius,
+                UnitPreferences {
+                    temperature: TemperatureUnit::Fahrenheit,
+                    ..
+                },
+            ) => Converted::Value {
+                value: value * 9.0 / 5.0 + 32.0,
+                symbol: "°F",
+            },
+            (
+                Unit::Meter,
+                UnitPreferences {
+                    length: LengthUnit::FeetInches,
+                    ..
+                },
+            ) => {
+                let inches = value / METERS_PER_INCH;
+                let feet = (inches / INCHES_PER_FOOT).trunc();
+                Converted::FeetInches {
+                    feet: feet as i64,
+                    inches: inches - feet * INCHES_PER_FOOT,
+                }
+            }
+            (
+                Unit::MillimeterOfMercury,
+                UnitPreferences {
+                    pressure: PressureUnit::Kilopascal,
+                    ..
+                },
+            ) => Converted::Value {
+                value: value * KPA_PER_MMHG,
+                symbol: "kPa",
+            },
+            _ => Converted::Exact(*self),
+        }
+    }
+
+    /// Returns a `Display` adapter that shows the value in the units chosen by `preferences`.
+    pub fn display_with<'a>(&'a self, preferences: &'a UnitPreferences) -> WithUnits<'a, Self> {
+        WithUnits {
+            value: self,
+            preferences,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quantity(value: i64, exponent: i64) -> Quantity {
+        Quantity {
+            value,
+            exponent,
+            unit: Unit::Kilogram,
+        }
+    }
+
+    #[test]
+    fn decimal_string_is_exact() {
+        let cases = [
+            (72350, -3, "72.35"),
+            (5, -3, "0.005"),
+            (-5, -3, "-0.005"),
+            (1200, -2, "12"),
+            (-1200, -2, "-12"),
+            (12, 2, "1200"),
+            (-12, 0, "-12"),
+            (0, -3, "0"),
+            (0, 5, "0"),
+            (i64::MIN, -2, "-92233720368547758.08"),
+            (i64::MAX, 0, "9223372036854775807"),
+        ];
+        for (value, exponent, expected) in cases {
+            assert_eq!(quantity(value, exponent).to_decimal_string(), expected);
+        }
+    }
+
+    #[test]
+    fn decimal_string_bounds_large_exponents() {
+        assert_eq!(quantity(72, -50).to_decimal_string(), "72e-50");
+        assert_eq!(quantity(-72, 41).to_decimal_string(), "-72e41");
+        assert_eq!(
+            quantity(1, i64::MAX).to_decimal_string(),
+            format!("1e{}", i64::MAX)
+        );
+        assert_eq!(quantity(1, 40).to_decimal_string().len(), 41);
+    }
+
+    #[test]
+    fn as_f64_does_not_wrap_the_exponent() {
+        assert_eq!(quantity(72350, -3).as_f64(), 72.35);
+        assert_eq!(quantity(12, 2).as_f64(), 1200.0);
+        // 2^32 would wrap to 0 as an i32
+        assert_eq!(quantity(5, 1 << 32).as_f64(), f64::INFINITY);
+        assert_eq!(quantity(5, -(1 << 32)).as_f64(), 0.0);
+        assert_eq!(quantity(-5, i64::MAX).as_f64(), f64::NEG_INFINITY);
+        assert_eq!(quantity(5, i64::MIN).as_f64(), 0.0);
+    }
+
+    #[test]
+    fn displays_the_unit_symbol() {
+        assert_eq!(quantity(72350, -3).to_string(), "72.35 kg");
+        let flag = Quantity {
+            value: 1,
+            exponent: 0,
+            unit: Unit::Unitless,
+        };
+        assert_eq!(flag.to_string(), "1");
+    }
+
+    fn stone() -> UnitPreferences {
+        UnitPreferences {
+            mass: MassUnit::Stone,
+            ..UnitPreferences::default()
+        }
+    }
+
+    #[test]
+    fn stone_rounds_before_splitting() {
+        let convert = |value, exponent| quantity(value, exponent).convert(&stone()).to_string();
+        // 139.994 lb
+        assert_eq!(convert(635, -1), "10 st 0.0 lb");
+        assert_eq!(convert(72350, -3), "11 st 5.5 lb");
+        // 139.94 lb stays below a whole stone
+        assert_eq!(convert(63475, -3), "9 st 13.9 lb");
+        assert_eq!(convert(0, 0), "0 st 0.0 lb");
+    }
+
+    #[test]
+    fn feet_round_before_splitting() {
+        let imperial = UnitPreferences::imperial();
+        let height = |value, exponent| Quantity {
+            value,
+            exponent,
+            unit: Unit::Meter,
+        };
+        // 71.992 in
+        assert_eq!(
+            height(18286, -4).convert(&imperial).to_string(),
+            "6 ft 0.0 in"
+        );
+        assert_eq!(
+            height(178, -2).convert(&imperial).to_string(),
+            "5 ft 10.1 in"
+        );
+        assert_eq!(
+            height(18288, -4).convert(&imperial).to_string(),
+            "6 ft 0.0 in"
+        );
+    }
+
+    #[test]
+    fn stone_keeps_the_exact_value() {
+        let converted = quantity(635, -1).convert(&stone());
+        let Converted::StonePounds { stone, pounds } = converted else {
+            panic!("expected stone and pounds, got {:?}", converted);
+        };
+        assert_eq!(stone, 9);
+        assert!((pounds - 13.994).abs() < 0.001);
+    }
+
+    #[test]
+    fn converts_other_units() {
+        let imperial = UnitPreferences::imperial();
+        assert_eq!(
+            quantity(72350, -3).convert(&imperial).to_string(),
+            "159.5 lb"
+        );
+
+        let temperature = Quantity {
+            value: 3700,
+            exponent: -2,
+            unit: Unit::Celsius,
+        };
+        assert_eq!(temperature.convert(&imperial).to_string(), "98.6 °F");
+
+        let systolic = Quantity {
+            value: 120,
+            exponent: 0,
+            unit: Unit::MillimeterOfMercury,
+        };
+        let kpa = UnitPreferences {
+            pressure: PressureUnit::Kilopascal,
+            ..UnitPreferences::default()
+        };
+        assert_eq!(systolic.convert(&kpa).to_string(), "16.00 kPa");
+        assert_eq!(systolic.display_with(&imperial).to_string(), "120 mmHg");
+    }
+
+    #[test]
+    fn preferred_units_are_kept_exact() {
+        let weight = quantity(72350, -3);
+        assert_eq!(
+            weight.convert(&UnitPreferences::metric()),
+            Converted::Exact(weight)
+        );
+        assert_eq!(
+            weight.display_with(&UnitPreferences::metric()).to_string(),
+            "72.35 kg"
+        );
+    }
+
+    #[test]
+    fn converts_distances() {
+        assert_eq!(
+            UnitPreferences::default()
+                .convert_distance(5234.0)
+                .to_string(),
+            "5234.0 m"
+        );
+        assert_eq!(
+            UnitPreferences::metric()
+                .convert_distance(5234.0)
+                .to_string(),
+            "5.23 km"
+        );
+        assert_eq!(
+            UnitPreferences::imperial()
+                .convert_distance(1609.344)
+                .to_string(),
+            "1.00 mi"
+        );
+    }
+}
@@ -0,0 +1,577 @@
+//! # Workout model
+//! Docs: https://developer.withings.com/api-reference/#tag/measure/operation/measurev2-getworkouts
+//! Response body from the measure v2 getworkouts endpoint
+
+use crate::models::date::Date;
+use crate::models::de::{self, seconds};
+use crate::models::meas::Attribution;
+use crate::models::unit::{Converted, UnitPreferences};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::time::Duration;
+
+/// Response from the API is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseWorkouts {
+    pub status: i64,
+    pub body: WorkoutsBody,
+}
+
+/// The body of the response is a JSON object that includes the following fields:
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkoutsBody {
+    pub series: Vec<Workout>,
+    /// Whether more workouts are available past this page
+    #[serde(default, deserialize_with = "de::bool_or_int")]
+    pub more: bool,
+    /// Offset to request the next page with, set when `more` is true
+    #[serde(default)]
+    pub offset: Option<i64>,
+}
+
+/// A workout recorded by a tracker or entered by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workout {
+    pub category: WorkoutCategory,
+    /// IANA timezone of the user during the workout
+    pub timezone: String,
+    /// Model id of the device that recorded the workout
+    #[serde(default)]
+    pub model: Option<i64>,
+    pub attrib: Attribution,
+    /// Start of the workout, as epoch seconds
+    pub startdate: i64,
+    /// End of the workout, as epoch seconds
+    pub enddate: i64,
+    /// The day of the workout, in the timezone of the user
+    pub date: Date,
+    #[serde(default)]
+    pub deviceid: Option<String>,
+    /// Last modification, as epoch seconds
+    #[serde(default)]
+    pub modified: Option<i64>,
+    #[serde(default)]
+    pub data: WorkoutData,
+}
+
+impl Workout {
+    /// Returns the time from the start to the end of the workout, pauses included.
+    pub fn duration(&self) -> Duration {
+        seconds(Some(self.enddate - self.startdate)).unwrap_or_default()
+    }
+}
+
+/// The data of a workout. Fields the device did not record, or not requested with
+/// `data_fields`, are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkoutData {
+    /// Active calories burned, in kcal
+    #[serde(default)]
+    pub calories: Option<f64>,
+    /// Intensity of the workout, from 0 to 100
+    #[serde(default)]
+    pub intensity: Option<i64>,
+    /// Distance entered by the user, in meters
+    #[serde(default)]
+    pub manual_distance: Option<f64>,
+    /// Calories entered by the user, in kcal
+    #[serde(default)]
+    pub manual_calories: Option<f64>,
+    /// Average heart rate in beats per minute
+    #[serde(default)]
+    pub hr_average: Option<i64>,
+    #[serde(default)]
+    pub hr_min: Option<i64>,
+    #[serde(default)]
+    pub hr_max: Option<i64>,
+    /// Seconds in the light heart rate zone
+    #[serde(default)]
+    pub hr_zone_0: Option<i64>,
+    /// Seconds in the moderate heart rate zone
+    #[serde(default)]
+    pub hr_zone_1: Option<i64>,
+    /// Seconds in the intense heart rate zone
+    #[serde(default)]
+    pub hr_zone_2: Option<i64>,
+    /// Seconds in the maximal heart rate zone
+    #[serde(default)]
+    pub hr_zone_3: Option<i64>,
+    /// Seconds of pause entered by the user
+    #[serde(default)]
+    pub pause_duration: Option<i64>,
+    /// Seconds of pause detected by the device
+    #[serde(default)]
+    pub algo_pause_duration: Option<i64>,
+    /// Average blood oxygen saturation in percent
+    #[serde(default)]
+    pub spo2_average: Option<f64>,
+    #[serde(default)]
+    pub steps: Option<i64>,
+    /// Distance in meters
+    #[serde(default)]
+    pub distance: Option<f64>,
+    /// Elevation climbed in meters
+    #[serde(default)]
+    pub elevation: Option<f64>,
+    /// Pool lengths swum
+    #[serde(default)]
+    pub pool_laps: Option<i64>,
+    /// Swim strokes
+    #[serde(default)]
+    pub strokes: Option<i64>,
+    /// Length of the pool in meters
+    #[serde(default)]
+    pub pool_length: Option<f64>,
+}
+
+impl WorkoutData {
+    /// Returns the time spent in each heart rate zone, from light to maximal.
+    pub fn hr_zones(&self) -> [Option<Duration>; 4] {
+        [
+            seconds(self.hr_zone_0),
+            seconds(self.hr_zone_1),
+            seconds(self.hr_zone_2),
+            seconds(self.hr_zone_3),
+        ]
+    }
+
+    /// Returns the distance in the unit chosen by `preferences`, the distance entered by the
+    /// user when the tracker did not measure one.
+    pub fn distance_in(&self, preferences: &UnitPreferences) -> Option<Converted> {
+        self.distance
+            .or(self.manual_distance)
+            .map(|meters| preferences.convert_distance(meters))
+    }
+}
+
+/// The sport of a workout, from the `category` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorkoutCategory {
+    Walk,
+    Run,
+    Hiking,
+    Skating,
+    Bmx,
+    Bicycling,
+    Swimming,
+    Surfing,
+    Kitesurfing,
+    Windsurfing,
+    Bodyboard,
+    Tennis,
+    TableTennis,
+    Squash,
+    Badminton,
+    LiftWeights,
+    Calisthenics,
+    Elliptical,
+    Pilates,
+    Basketball,
+    Soccer,
+    Football,
+    Rugby,
+    Volleyball,
+    WaterPolo,
+    HorseRiding,
+    Golf,
+    Yoga,
+    Dancing,
+    Boxing,
+    Fencing,
+    Wrestling,
+    MartialArts,
+    Skiing,
+    Snowboarding,
+    Other,
+    NoActivity,
+    Rowing,
+    Zumba,
+    Baseball,
+    Handball,
+    Hockey,
+    IceHockey,
+    Climbing,
+    IceSkating,
+    MultiSport,
+    IndoorWalk,
+    IndoorRunning,
+    IndoorCycling,
+    /// A `category` value this library does not know
+    Unknown(i64),
+}
+
+impl WorkoutCategory {
+    /// Every workout category known to this library, in id order.
+    pub const ALL: [WorkoutCategory; 49] = [
+        WorkoutCategory::Walk,
+        WorkoutCategory::Run,
+        WorkoutCategory::Hiking,
+        WorkoutCategory::Skating,
+        WorkoutCategory::Bmx,
+        WorkoutCategory::Bicycling,
+        WorkoutCategory::Swimming,
+        WorkoutCategory::Surfing,
+        WorkoutCategory::Kitesurfing,
+        WorkoutCategory::Windsurfing,
+        WorkoutCategory::Bodyboard,
+        WorkoutCategory::Tennis,
+        WorkoutCategory::TableTennis,
+        WorkoutCategory::Squash,
+        WorkoutCategory::Badminton,
+        WorkoutCategory::LiftWeights,
+        WorkoutCategory::Calisthenics,
+        WorkoutCategory::Elliptical,
+        WorkoutCategory::Pilates,
+        WorkoutCategory::Basketball,
+        WorkoutCategory::Soccer,
+        WorkoutCategory::Football,
+        WorkoutCategory::Rugby,
+        WorkoutCategory::Volleyball,
+        WorkoutCategory::WaterPolo,
+        WorkoutCategory::HorseRiding,
+        WorkoutCategory::Golf,
+        WorkoutCategory::Yoga,
+        WorkoutCategory::Dancing,
+        WorkoutCategory::Boxing,
+        WorkoutCategory::Fencing,
+        WorkoutCategory::Wrestling,
+        WorkoutCategory::MartialArts,
+        WorkoutCategory::Skiing,
+        WorkoutCategory::Snowboarding,
+        WorkoutCategory::Other,
+        WorkoutCategory::NoActivity,
+        WorkoutCategory::Rowing,
+        WorkoutCategory::Zumba,
+        WorkoutCategory::Baseball,
+        WorkoutCategory::Handball,
+        WorkoutCategory::Hockey,
+        WorkoutCategory::IceHockey,
+        WorkoutCategory::Climbing,
+        WorkoutCategory::IceSkating,
+        WorkoutCategory::MultiSport,
+        WorkoutCategory::IndoorWalk,
+        WorkoutCategory::IndoorRunning,
+        WorkoutCategory::IndoorCycling,
+    ];
+
+    /// Returns the `category` value used by the API.
+    pub fn id(&self) -> i64 {
+        match self {
+            WorkoutCategory::Walk => 1,
+            WorkoutCategory::Run => 2,
+            WorkoutCategory::Hiking => 3,
+            WorkoutCategory::Skating => 4,
+            WorkoutCategory::Bmx => 5,
+            WorkoutCategory::Bicycling => 6,
+            WorkoutCategory::Swimming => 7,
+            WorkoutCategory::Surfing => 8,
+            WorkoutCategory::Kitesurfing => 9,
+            WorkoutCategory::Windsurfing => 10,
+            WorkoutCategory::Bodyboard => 11,
+            WorkoutCategory::Tennis => 12,
+            WorkoutCategory::TableTennis => 13,
+            WorkoutCategory::Squash => 14,
+            WorkoutCategory::Badminton => 15,
+            WorkoutCategory::LiftWeights => 16,
+            WorkoutCategory::Calisthenics => 17,
+            WorkoutCategory::Elliptical => 18,
+            WorkoutCategory::Pilates => 19,
+            WorkoutCategory::Basketball => 20,
+            WorkoutCategory::Soccer => 21,
+            WorkoutCategory::Football => 22,
+            WorkoutCategory::Rugby => 23,
+            WorkoutCategory::Volleyball => 24,
+            WorkoutCategory::WaterPolo => 25,
+            WorkoutCategory::HorseRiding => 26,
+            WorkoutCategory::Golf => 27,
+            WorkoutCategory::Yoga => 28,
+            WorkoutCategory::Dancing => 29,
+            WorkoutCategory::Boxing => 30,
+            WorkoutCategory::Fencing => 31,
+            WorkoutCategory::Wrestling => 32,
+            WorkoutCategory::MartialArts => 33,
+            WorkoutCategory::Skiing => 34,
+            WorkoutCategory::Snowboarding => 35,
+            WorkoutCategory::Other => 36,
+            WorkoutCategory::NoActivity => 128,
+            WorkoutCategory::Rowing => 187,
+            WorkoutCategory::Zumba => 188,
+            WorkoutCategory::Baseball => 191,
+            WorkoutCategory::Handball => 192,
+            WorkoutCategory::Hockey => 193,
+            WorkoutCategory::IceHockey => 194,
+            WorkoutCategory::Climbing => 195,
+            WorkoutCategory::IceSkating => 196,
+            WorkoutCategory::MultiSport => 272,
+            WorkoutCategory::IndoorWalk => 306,
+            WorkoutCategory::IndoorRunning => 307,
+            WorkoutCategory::IndoorCycling => 308,
+            WorkoutCategory::Unknown(id) => *id,
+        }
+    }
+
+    /// Returns the `WorkoutCategory` for a `category` value, `Unknown` if the value is not known.
+    pub fn from_id(id: i64) -> Self {
+        match id {
+            1 => WorkoutCategory::Walk,
+            2 => WorkoutCategory::Run,
+            3 => WorkoutCategory::Hiking,
+            4 => WorkoutCategory::Skating,
+            5 => WorkoutCategory::Bmx,
+            6 => WorkoutCategory::Bicycling,
+            7 => WorkoutCategory::Swimming,
+            8 => WorkoutCategory::Surfing,
+            9 => WorkoutCategory::Kitesurfing,
+            10 => WorkoutCategory::Windsurfing,
+            11 => WorkoutCategory::Bodyboard,
+            12 => WorkoutCategory::Tennis,
+            13 => WorkoutCategory::TableTennis,
+            14 => WorkoutCategory::Squash,
+            15 => WorkoutCategory::Badminton,
+            16 => WorkoutCategory::LiftWeights,
+            17 => WorkoutCategory::Calisthenics,
+            18 => WorkoutCategory::Elliptical,
+            19 => WorkoutCategory::Pilates,
+            20 => WorkoutCategory::Basketball,
+            21 => WorkoutCategory::Soccer,
+            22 => WorkoutCategory::Football,
+            23 => WorkoutCategory::Rugby,
+            24 => WorkoutCategory::Volleyball,
+            25 => WorkoutCategory::WaterPolo,
+            26 => WorkoutCategory::HorseRiding,
+            27 => WorkoutCategory::Golf,
+            28 => WorkoutCategory::Yoga,
+            29 => WorkoutCategory::Dancing,
+            30 => WorkoutCategory::Boxing,
+            31 => WorkoutCategory::Fencing,
+            32 => WorkoutCategory::Wrestling,
+            33 => WorkoutCategory::MartialArts,
+            34 => WorkoutCategory::Skiing,
+            35 => WorkoutCategory::Snowboarding,
+            36 => WorkoutCategory::Other,
+            128 => WorkoutCategory::NoActivity,
+            187 => WorkoutCategory::Rowing,
+            188 => WorkoutCategory::Zumba,
+            191 => WorkoutCategory::Baseball,
+            192 => WorkoutCategory::Handball,
+            193 => WorkoutCategory::Hockey,
+            194 => WorkoutCategory::IceHockey,
+            195 => WorkoutCategory::Climbing,
+            196 => WorkoutCategory::IceSkating,
+            272 => WorkoutCategory::MultiSport,
+            306 => WorkoutCategory::IndoorWalk,
+            307 => WorkoutCategory::IndoorRunning,
+            308 => WorkoutCategory::IndoorCycling,
+            id => WorkoutCategory::Unknown(id),
+        }
+    }
+
+    /// Returns a human readable name, e.g. `table tennis`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorkoutCategory::Walk => "walk",
+            WorkoutCategory::Run => "run",
+            WorkoutCategory::Hiking => "hiking",
+            WorkoutCategory::Skating => "skating",
+            WorkoutCategory::Bmx => "BMX",
+            WorkoutCategory::Bicycling => "bicycling",
+            WorkoutCategory::Swimming => "swimming",
+            WorkoutCategory::Surfing => "surfing",
+            WorkoutCategory::Kitesurfing => "kitesurfing",
+            WorkoutCategory::Windsurfing => "windsurfing",
+            WorkoutCategory::Bodyboard => "bodyboard",
+            WorkoutCategory::Tennis => "tennis",
+            WorkoutCategory::TableTennis => "table tennis",
+            WorkoutCategory::Squash => "squash",
+            WorkoutCategory::Badminton => "badminton",
+            WorkoutCategory::LiftWeights => "lift weights",
+            WorkoutCategory::Calisthenics => "calisthenics",
+            WorkoutCategory::Elliptical => "elliptical",
+            WorkoutCategory::Pilates => "pilates",
+            WorkoutCategory::Basketball => "basketball",
+            WorkoutCategory::Soccer => "soccer",
+            WorkoutCategory::Football => "football",
+            WorkoutCategory::Rugby => "rugby",
+            WorkoutCategory::Volleyball => "volleyball",
+            WorkoutCategory::WaterPolo => "water polo",
+            WorkoutCategory::HorseRiding => "horse riding",
+            WorkoutCategory::Golf => "golf",
+            WorkoutCategory::Yoga => "yoga",
+            WorkoutCategory::Dancing => "dancing",
+            WorkoutCategory::Boxing => "boxing",
+            WorkoutCategory::Fencing => "fencing",
+            WorkoutCategory::Wrestling => "wrestling",
+            WorkoutCategory::MartialArts => "martial arts",
+            WorkoutCategory::Skiing => "skiing",
+            WorkoutCategory::Snowboarding => "snowboarding",
+            WorkoutCategory::Other => "other",
+            WorkoutCategory::NoActivity => "no activity",
+            WorkoutCategory::Rowing => "rowing",
+            WorkoutCategory::Zumba => "zumba",
+            WorkoutCategory::Baseball => "baseball",
+            WorkoutCategory::Handball => "handball",
+            WorkoutCategory::Hockey => "hockey",
+            WorkoutCategory::IceHockey => "ice hockey",
+            WorkoutCategory::Climbing => "climbing",
+            WorkoutCategory::IceSkating => "ice skating",
+            WorkoutCategory::MultiSport => "multi-sport",
+            WorkoutCategory::IndoorWalk => "indoor walk",
+            WorkoutCategory::IndoorRunning => "indoor running",
+            WorkoutCategory::IndoorCycling => "indoor cycling",
+            WorkoutCategory::Unknown(_) => "unknown",
+        }
+    }
+}
+
+/// Displays the human readable name.
+impl fmt::Display for WorkoutCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Serializes as the `category` value used by the API.
+impl Serialize for WorkoutCategory {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.id())
+    }
+}
+
+/// Deserializes from the `category` value used by the API, keeping unknown values.
+impl<'de> Deserialize<'de> for WorkoutCategory {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i64::deserialize(deserializer).map(WorkoutCategory::from_id)
+    }
+}
+
+/// The fields of the workout data that can be requested with `data_fields`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorkoutField {
+    Calories,
+    Intensity,
+    ManualDistance,
+    ManualCalories,
+    HrAverage,
+    HrMin,
+    HrMax,
+    HrZone0,
+    HrZone1,
+    HrZone2,
+    HrZone3,
+    PauseDuration,
+    AlgoPauseDuration,
+    Spo2Average,
+    Steps,
+    Distance,
+    Elevation,
+    PoolLaps,
+    Strokes,
+    PoolLength,
+}
+
+impl WorkoutField {
+    /// Returns the name of the field in the API.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorkoutField::Calories => "calories",
+            WorkoutField::Intensity => "intensity",
+            WorkoutField::ManualDistance => "manual_distance",
+            WorkoutField::ManualCalories => "manual_calories",
+            WorkoutField::HrAverage => "hr_average",
+            WorkoutField::HrMin => "hr_min",
+            WorkoutField::HrMax => "hr_max",
+            WorkoutField::HrZone0 => "hr_zone_0",
+            WorkoutField::HrZone1 => "hr_zone_1",
+            WorkoutField::HrZone2 => "hr_zone_2",
+            WorkoutField::HrZone3 => "hr_zone_3",
+            WorkoutField::PauseDuration => "pause_duration",
+            WorkoutField::AlgoPauseDuration => "algo_pause_duration",
+            WorkoutField::Spo2Average => "spo2_average",
+            WorkoutField::Steps => "steps",
+            WorkoutField::Distance => "distance",
+            WorkoutField::Elevation => "elevation",
+            WorkoutField::PoolLaps => "pool_laps",
+            WorkoutField::Strokes => "strokes",
+            WorkoutField::PoolLength => "pool_length",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_category_round_trips_through_its_id() {
+        let mut ids = HashSet::new();
+        let mut names = HashSet::new();
+        for category in WorkoutCategory::ALL {
+            assert_eq!(WorkoutCategory::from_id(category.id()), category);
+            assert!(ids.insert(category.id()), "{:?} shares its id", category);
+            assert!(
+                names.insert(category.name()),
+                "{:?} shares its name",
+                category
+            );
+            assert!(!matches!(category, WorkoutCategory::Unknown(_)));
+        }
+    }
+
+    #[test]
+    fn unknown_category_is_kept_through_serde() {
+        let category: WorkoutCategory = serde_json::from_value(json!(999)).unwrap();
+        assert_eq!(category, WorkoutCategory::Unknown(999));
+        assert_eq!(serde_json::to_value(category).unwrap(), json!(999));
+    }
+
+    #[test]
+    fn workouts_body_accepts_partial_data() {
+        let body: WorkoutsBody = serde_json::from_value(json!({
+            "series": [{
+                "category": 2,
+                "timezone": "Europe/Paris",
+                "model": 1059,
+                "attrib": 0,
+                "startdate": 1706108118,
+                "enddate": 1706109918,
+                "date": "2024-01-24",
+                "deviceid": "abc",
+                "data": { "calories": 312.5, "distance": 5012.0, "hr_zone_1": 900 }
+            }, {
+                "category": 16,
+                "timezone": "Europe/Paris",
+                "attrib": 2,
+                "startdate": 1706194518,
+                "enddate": 1706196318,
+                "date": "2024-01-25",
+                "data": { "manual_distance": 1200.0 }
+            }],
+            "more": 0,
+            "offset": 0
+        }))
+        .unwrap();
+        assert!(!body.more);
+
+        let run = &body.series[0];
+        assert_eq!(run.category, WorkoutCategory::Run);
+        assert_eq!(run.duration(), Duration::from_secs(1800));
+        assert_eq!(run.data.calories, Some(312.5));
+        assert_eq!(run.data.hr_average, None);
+        assert_eq!(
+            run.data.hr_zones(),
+            [None, Some(Duration::from_secs(900)), None, None]
+        );
+
+        let manual = &body.series[1];
+        assert_eq!(manual.attrib, Attribution::Manual);
+        assert_eq!(manual.model, None);
+        assert_eq!(manual.data.distance, None);
+        let metric = UnitPreferences::default();
+        assert_eq!(
+            manual.data.distance_in(&metric).map(|d| d.as_f64()),
+            Some(1200.0)
+        );
+    }
+}
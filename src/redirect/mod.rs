@@ -12,7 +12,6 @@ use url::Url;
 /// The server is stopped after the redirect is received and the code and state are returned.
 /// If the code or state are not received, the program will exit.
 /// Returns HashMap with code and state
-
 pub mod server {
     use super::*;
     #[allow(unused_assignments)]
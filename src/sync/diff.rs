@@ -0,0 +1,210 @@
+//! # diff
+//! Compares a stored set of measure groups against a fresh fetch to find the groups that were
+//! added, updated or removed in the meantime.
+//!
+//! A `lastupdate` sync never reports deleted groups, and a weigh-in reassigned to another user in
+//! the Withings app simply disappears. Removals are only visible by fetching a window again and
+//! comparing it with what was stored for the same window, which is what this module does.
+//!
+//! Only compare groups fetched with the same measure types and category, otherwise every stored
+//! group of another type shows up as removed.
+
+use crate::api::{
+    client::WithingsClient,
+    measure::{self, MeasurementParams},
+    params::ParamsError,
+};
+use crate::models::Measuregrp;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// The changes between a stored set of measure groups and a fresh fetch, keyed by `grpid`.
+#[derive(Debug, Clone, Default)]
+pub struct MeasureDiff {
+    /// Groups in the fresh fetch that were not stored.
+    pub added: BTreeMap<i64, Measuregrp>,
+    /// Groups modified since they were stored, with their fresh content.
+    pub updated: BTreeMap<i64, Measuregrp>,
+    /// Stored groups inside the fetched window that are gone from the fresh fetch.
+    pub removed: BTreeMap<i64, Measuregrp>,
+}
+
+impl MeasureDiff {
+    /// Returns `true` if the stored groups are up to date.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// The range of measure dates, as epoch seconds, covered by a fetch. `None` bounds are open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Window {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+}
+
+impl Window {
+    /// A window covering every date, for a fetch of the whole history.
+    pub fn all() -> Self {
+        Window::default()
+    }
+
+    /// Returns `true` if the window covers the epoch seconds `date`.
+    pub fn contains(&self, date: i64) -> bool {
+        self.start.is_none_or(|start| date >= start) && self.end.is_none_or(|end| date <= end)
+    }
+}
+
+/// Compares stored groups against a fresh fetch of `window`.
+///
+/// A group is updated when its `modified` time is newer than the stored one. Stored groups dated
+/// outside `window` are never reported as removed, since the fresh fetch could not contain them.
+pub fn diff_groups<'a, 'b>(
+    stored: impl IntoIterator<Item = &'a Measuregrp>,
+    fresh: impl IntoIterator<Item = &'b Measuregrp>,
+    window: Window,
+) -> MeasureDiff {
+    let mut stored: BTreeMap<i64, &Measuregrp> = stored
+        .into_iter()
+        .map(|group| (group.grpid(), group))
+        .collect();
+    let mut diff = MeasureDiff::default();
+
+    for group in fresh {
+        match stored.remove(&group.grpid()) {
+            None => {
+                diff.added.insert(group.grpid(), group.clone());
+            }
+            Some(previous) if group.modified() > previous.modified() => {
+                diff.updated.insert(group.grpid(), group.clone());
+            }
+            Some(_) => {}
+        }
+    }
+
+    diff.removed = stored
+        .into_values()
+        .filter(|group| window.contains(group.date()))
+        .map(|group| (group.grpid(), group.clone()))
+        .collect();
+    diff
+}
+
+/// Fetches every page for `params` and compares the result against the stored groups.
+///
+/// The window is taken from the `startdate`/`enddate` of `params`. Parameters with `lastupdate`
+/// only return changed groups, so they cannot reveal removals and are rejected.
+///
+/// # Errors
+/// - Returns `ParamsError::LastUpdateNotSupported` if `params` has a `lastupdate`.
+/// - Returns an error if a request fails.
+pub fn fetch_and_diff<'a>(
+    client: &WithingsClient,
+    params: &MeasurementParams,
+    stored: impl IntoIterator<Item = &'a Measuregrp>,
+) -> Result<MeasureDiff, Box<dyn Error>> {
+    if params.lastupdate().is_some() {
+        return Err(Box::new(ParamsError::LastUpdateNotSupported));
+    }
+    let fresh = measure::get_measure_groups(client, params).collect::<Result<Vec<_>, _>>()?;
+    let window = Window {
+        start: params.startdate(),
+        end: params.enddate(),
+    };
+    Ok(diff_groups(stored, &fresh, window))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(grpid: i64, date: i64, modified: i64) -> Measuregrp {
+        serde_json::from_value(serde_json::json!({
+            "grpid": grpid,
+            "attrib": 0,
+            "date": date,
+            "created": date,
+            "modified": modified,
+            "category": 1,
+            "measures": [],
+            "comment": null
+        }))
+        .unwrap()
+    }
+
+    fn grpids(groups: &BTreeMap<i64, Measuregrp>) -> Vec<i64> {
+        groups.keys().copied().collect()
+    }
+
+    #[test]
+    fn finds_added_updated_and_removed_groups() {
+        let stored = [group(1, 100, 100), group(2, 200, 200), group(3, 300, 300)];
+        let fresh = [group(1, 100, 100), group(2, 200, 250), group(4, 400, 400)];
+
+        let diff = diff_groups(&stored, &fresh, Window::all());
+        assert_eq!(grpids(&diff.added), vec![4]);
+        assert_eq!(grpids(&diff.updated), vec![2]);
+        assert_eq!(diff.updated[&2].modified(), 250);
+        assert_eq!(grpids(&diff.removed), vec![3]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn ignores_stored_groups_outside_the_window() {
+        let stored = [group(1, 100, 100), group(2, 200, 200), group(3, 300, 300)];
+        let fresh = [group(2, 200, 200)];
+        let window = Window {
+            start: Some(150),
+            end: Some(250),
+        };
+
+        let diff = diff_groups(&stored, &fresh, window);
+        assert!(diff.is_empty());
+
+        let window = Window {
+            start: Some(150),
+            end: None,
+        };
+        assert_eq!(
+            grpids(&diff_groups(&stored, &fresh, window).removed),
+            vec![3]
+        );
+    }
+
+    #[test]
+    fn an_older_fresh_copy_is_not_an_update() {
+        let stored = [group(1, 100, 200)];
+        let fresh = [group(1, 100, 150)];
+        assert!(diff_groups(&stored, &fresh, Window::all()).is_empty());
+    }
+
+    #[test]
+    fn window_bounds_are_inclusive() {
+        let window = Window {
+            start: Some(10),
+            end: Some(20),
+        };
+        assert!(window.contains(10));
+        assert!(window.contains(20));
+        assert!(!window.contains(9));
+        assert!(!window.contains(21));
+        assert!(Window::all().contains(i64::MIN));
+    }
+
+    #[test]
+    fn fetch_and_diff_rejects_lastupdate() {
+        let client = WithingsClient::builder()
+            .base_url("http://127.0.0.1:9")
+            .build()
+            .unwrap();
+        let params = MeasurementParams::builder()
+            .lastupdate(std::time::UNIX_EPOCH)
+            .build()
+            .unwrap();
+        let err = fetch_and_diff(&client, &params, &[]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ParamsError>(),
+            Some(&ParamsError::LastUpdateNotSupported)
+        );
+    }
+}
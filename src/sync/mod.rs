@@ -0,0 +1,283 @@
+//! # sync
+//! Incremental sync of measure groups built on the `lastupdate` parameter.
+//!
+//! The `Syncer` remembers the `updatetime` of the last successful sync per user and per data
+//! type in a `CursorStore`. Each sync only fetches what changed since then, following pagination,
+//! and the cursor is only advanced once every page has been fetched. Running the same sync twice
+//! is harmless, which keeps nightly jobs idempotent.
+//!
+//! The diff submodule detects groups that were deleted or reassigned since they were stored.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use withings_rs::api::{measure::MeasurementParams, WithingsClient};
+//! use withings_rs::models::MeasureType;
+//! use withings_rs::sync::{FileCursorStore, Syncer};
+//!
+//! let client = WithingsClient::default();
+//! let syncer = Syncer::new(FileCursorStore::default());
+//! let params = MeasurementParams::builder()
+//!     .meastype(MeasureType::Weight)
+//!     .build()
+//!     .unwrap();
+//!
+//! let result = syncer.sync_measures(&client, "me", &params).unwrap();
+//! for (grpid, group) in &result.added {
+//!     println!("new group {} with {} measures", grpid, group.measures.len());
+//! }
+//! ```
+
+use crate::api::{
+    client::WithingsClient,
+    config::{read_from_file, save_to_file},
+    measure::{self, MeasurementParams},
+};
+use crate::models::Measuregrp;
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::{env, error::Error, path::Path};
+
+pub mod diff;
+
+/// Persists the sync cursor, the `updatetime` of the last successful sync, per user and per data
+/// type.
+pub trait CursorStore {
+    /// Returns the cursor, or `None` if the data type was never synced for the user.
+    fn load(&self, user: &str, data_type: &str) -> Result<Option<i64>, Box<dyn Error>>;
+    /// Stores the cursor after a successful sync.
+    fn save(&self, user: &str, data_type: &str, updatetime: i64) -> Result<(), Box<dyn Error>>;
+}
+
+/// Cursors keyed by user, then by data type
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cursors {
+    users: BTreeMap<String, BTreeMap<String, i64>>,
+}
+
+/// Stores the cursors in a JSON file.
+#[derive(Debug, Clone)]
+pub struct FileCursorStore {
+    path: String,
+}
+
+impl FileCursorStore {
+    /// Stores the cursors in the file at `path`.
+    pub fn new(path: impl Into<String>) -> Self {
+        FileCursorStore { path: path.into() }
+    }
+
+    /// Reads the cursors, starting empty if the file does not exist yet.
+    fn read(&self) -> Result<Cursors, Box<dyn Error>> {
+        if Path::new(&self.path).exists() {
+            read_from_file(&self.path)
+        } else {
+            Ok(Cursors::default())
+        }
+    }
+}
+
+/// Uses the file named by the `WITHINGS_SYNC_FILE` environment variable, `sync.json` by default.
+impl Default for FileCursorStore {
+    fn default() -> Self {
+        let path = env::var("WITHINGS_SYNC_FILE").unwrap_or_else(|_| "sync.json".to_string());
+        info!("Using sync file: {}", path);
+        Self::new(path)
+    }
+}
+
+impl CursorStore for FileCursorStore {
+    fn load(&self, user: &str, data_type: &str) -> Result<Option<i64>, Box<dyn Error>> {
+        let cursors = self.read()?;
+        Ok(cursors
+            .users
+            .get(user)
+            .and_then(|data_types| data_types.get(data_type))
+            .copied())
+    }
+
+    fn save(&self, user: &str, data_type: &str, updatetime: i64) -> Result<(), Box<dyn Error>> {
+        let mut cursors = self.read()?;
+        cursors
+            .users
+            .entry(user.to_string())
+            .or_default()
+            .insert(data_type.to_string(), updatetime);
+        save_to_file(&self.path, &cursors)
+    }
+}
+
+/// The measure groups created or modified since the previous sync, keyed by `grpid`.
+#[derive(Debug, Clone, Default)]
+pub struct SyncResult {
+    /// Groups created since the previous sync. Every group on the first sync.
+    pub added: BTreeMap<i64, Measuregrp>,
+    /// Groups created before the previous sync and modified since.
+    pub modified: BTreeMap<i64, Measuregrp>,
+    /// The cursor the sync started from, `None` on the first sync.
+    pub since: Option<i64>,
+    /// The new cursor, stored for the next sync.
+    pub updatetime: i64,
+}
+
+impl SyncResult {
+    /// Returns `true` if nothing changed since the previous sync.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty()
+    }
+
+    /// Returns the added and modified groups.
+    pub fn groups(&self) -> impl Iterator<Item = &Measuregrp> {
+        self.added.values().chain(self.modified.values())
+    }
+}
+
+/// Fetches what changed since the previous sync and advances the cursor.
+pub struct Syncer<S: CursorStore> {
+    store: S,
+}
+
+impl<S: CursorStore> Syncer<S> {
+    /// Creates a syncer that keeps its cursors in `store`.
+    pub fn new(store: S) -> Self {
+        Syncer { store }
+    }
+
+    /// Returns the cursor store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Fetches the measure groups created or modified since the previous sync of the same user
+    /// and data type, then stores the new cursor.
+    ///
+    /// The data type is derived from the measure types and category of `params`, so syncing
+    /// weight and blood pressure separately keeps separate cursors.
+    ///
+    /// # Arguments
+    /// - `client`: The `WithingsClient` used to send the requests.
+    /// - `user`: Any stable identifier of the user the client's token belongs to.
+    /// - `params`: The measures to sync. Must not have a date range.
+    ///
+    /// # Errors
+    /// - Returns an error if `params` has a date range, a request fails or the cursor cannot be
+    ///   read or stored. The cursor is not advanced when an error is returned.
+    pub fn sync_measures(
+        &self,
+        client: &WithingsClient,
+        user: &str,
+        params: &MeasurementParams,
+    ) -> Result<SyncResult, Box<dyn Error>> {
+        let data_type = measure_data_type(params);
+        let since = self.store.load(user, &data_type)?;
+        let params = params.with_lastupdate(since.unwrap_or(0))?;
+        info!("Syncing {} for {} since {:?}", data_type, user, since);
+
+        let mut result = SyncResult {
+            since,
+            ..Default::default()
+        };
+        let mut updatetime = None;
+        for page in measure::get_measurement_pages(client, &params) {
+            let body = page?.body;
+            // The first page is the oldest view of the data, anything changed while paging is
+            // picked up again by the next sync.
+            updatetime.get_or_insert(body.updatetime());
+            for group in body.measuregrps {
+                let is_new = since.is_none_or(|since| group.created() >= since);
+                if is_new {
+                    result.added.insert(group.grpid(), group);
+                } else {
+                    result.modified.insert(group.grpid(), group);
+                }
+            }
+        }
+
+        result.updatetime = updatetime.unwrap_or_default();
+        self.store.save(user, &data_type, result.updatetime)?;
+        info!(
+            "Synced {} added and {} modified groups",
+            result.added.len(),
+            result.modified.len()
+        );
+        Ok(result)
+    }
+}
+
+/// Builds the cursor key of a measure request from its measure types and category.
+///
+/// The measure types are sorted and deduplicated, so the same types in any order share a cursor.
+fn measure_data_type(params: &MeasurementParams) -> String {
+    let query = params.to_query_params();
+    let mut meastypes: Vec<i64> = ["meastype", "meastypes"]
+        .iter()
+        .filter_map(|name| query.get(name))
+        .flat_map(|value| value.split(','))
+        .filter_map(|meastype| meastype.parse().ok())
+        .collect();
+    meastypes.sort_unstable();
+    meastypes.dedup();
+
+    let mut key = String::from("getmeas");
+    match meastypes.as_slice() {
+        [] => {}
+        [meastype] => key.push_str(&format!(":meastype={}", meastype)),
+        meastypes => {
+            let meastypes: Vec<String> = meastypes.iter().map(i64::to_string).collect();
+            key.push_str(&format!(":meastypes={}", meastypes.join(",")));
+        }
+    }
+    if let Some(category) = query.get("category") {
+        key.push_str(&format!(":category={}", category));
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{meas::CategoryType, MeasureType};
+
+    fn params(meastypes: &[MeasureType]) -> MeasurementParams {
+        MeasurementParams::builder()
+            .meastypes(meastypes.iter().copied())
+            .category(CategoryType::Measures)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn data_type_ignores_the_order_and_duplicates_of_meastypes() {
+        use MeasureType::{FatRatio, Weight};
+        let key = measure_data_type(&params(&[Weight, FatRatio]));
+        assert_eq!(key, "getmeas:meastypes=1,6:category=1");
+        assert_eq!(
+            measure_data_type(&params(&[FatRatio, Weight, FatRatio])),
+            key
+        );
+        assert_eq!(
+            measure_data_type(&params(&[Weight, Weight])),
+            measure_data_type(&params(&[Weight]))
+        );
+        assert_eq!(measure_data_type(&params(&[])), "getmeas:category=1");
+    }
+
+    #[test]
+    fn file_store_round_trips_and_replaces_the_file() {
+        let path = std::env::temp_dir().join(format!("withings-sync-{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let store = FileCursorStore::new(path.clone());
+
+        assert_eq!(store.load("me", "getmeas").unwrap(), None);
+        store.save("me", "getmeas", 10).unwrap();
+        store.save("me", "getmeas", 20).unwrap();
+        store.save("you", "getmeas", 30).unwrap();
+        assert_eq!(store.load("me", "getmeas").unwrap(), Some(20));
+        assert_eq!(store.load("you", "getmeas").unwrap(), Some(30));
+        assert_eq!(store.load("me", "other").unwrap(), None);
+        assert!(!Path::new(&format!("{}.tmp", path)).exists());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "mock")]
+//! Replays the recorded cassettes in `tests/fixtures` to lock in real payload shapes.
+
+use serde_json::json;
+use withings_rs::api::{auth, client::StaticToken, measure, WithingsClient};
+use withings_rs::mock::cassette::{Cassette, Recorder};
+use withings_rs::mock::{MockResponse, MockServer};
+use withings_rs::models::{meas::CategoryType, Attribution, MeasureType};
+
+fn fixture(name: &str) -> Cassette {
+    Cassette::load(format!(
+        "{}/tests/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    ))
+    .unwrap()
+}
+
+fn client(server: &MockServer) -> WithingsClient {
+    WithingsClient::builder()
+        .token_source(StaticToken("token".to_string()))
+        .base_url(server.base_url())
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn token_response_accepts_userid_as_string_and_int() {
+    let config =
+        std::env::temp_dir().join(format!("withings-cassette-{}.json", std::process::id()));
+    std::fs::write(
+        &config,
+        json!({ "access_token": "old", "refresh_token": "refresh" }).to_string(),
+    )
+    .unwrap();
+    std::env::set_var("WITHINGS_CONFIG_FILE", &config);
+
+    let server = MockServer::replay(&fixture("oauth2_token.json")).unwrap();
+    let client = client(&server);
+
+    // The first response sends userid as a string, the second as an int
+    for _ in 0..2 {
+        let token =
+            auth::refresh_token_with(&client, "id".to_string(), "secret".to_string()).unwrap();
+        assert_eq!(token, "REDACTED");
+    }
+
+    std::fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn getmeas_pages_follow_the_recorded_offset() {
+    let server = MockServer::replay(&fixture("getmeas.json")).unwrap();
+    let params = measure::MeasurementParams::builder()
+        .meastypes([MeasureType::Weight, MeasureType::FatRatio])
+        .category(CategoryType::Measures)
+        .build()
+        .unwrap();
+
+    let groups: Vec<_> = measure::get_measure_groups(&client(&server), &params)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(groups.len(), 2);
+
+    let device = &groups[0];
+    assert_eq!(device.grpid(), 5195375391);
+    assert_eq!(device.model(), Some("Body+"));
+    assert_eq!(device.comment(), None);
+    let weight = device.measure(MeasureType::Weight).unwrap();
+    assert_eq!(weight.decoded_value(), 72.48);
+
+    let manual = &groups[1];
+    assert_eq!(manual.attribution(), Attribution::Manual);
+    assert_eq!(manual.deviceid(), None);
+    assert_eq!(manual.modelid(), None);
+    assert_eq!(manual.comment(), Some("After breakfast"));
+
+    let requests = server.requests();
+    assert_eq!(requests[0].param("offset"), None);
+    assert_eq!(requests[1].param("offset"), Some("1"));
+}
+
+#[test]
+fn recorder_scrubs_secrets_and_uses_the_builder_transport() {
+    let upstream = MockServer::start().unwrap();
+    upstream.respond(
+        "v2/oauth2",
+        "requesttoken",
+        MockResponse::token("secret-access", "secret-refresh"),
+    );
+
+    let path = std::env::temp_dir().join(format!("withings-recorder-{}.json", std::process::id()));
+    let builder = WithingsClient::builder().user_agent("recorder-test/1.0");
+    let recorder = Recorder::start_with(builder, &upstream.base_url(), &path).unwrap();
+
+    let http = reqwest::blocking::Client::new();
+    let response = http
+        .post(format!("{}/v2/oauth2", recorder.base_url()))
+        .form(&[
+            ("action", "requesttoken"),
+            ("client_secret", "my-secret"),
+            ("code", "my-code"),
+        ])
+        .send()
+        .unwrap();
+    assert!(response.text().unwrap().contains("secret-access"));
+
+    let cassette = recorder.finish().unwrap();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    for secret in ["secret-access", "secret-refresh", "my-secret", "my-code"] {
+        assert!(!saved.contains(secret), "{} was recorded", secret);
+    }
+
+    let interaction = &cassette.interactions[0];
+    assert_eq!(interaction.request.params["action"], "requesttoken");
+    assert_eq!(interaction.request.params["client_secret"], "REDACTED");
+    assert_eq!(
+        interaction.response.body["body"]["access_token"],
+        "REDACTED"
+    );
+
+    assert_eq!(
+        upstream.requests()[0].header("User-Agent"),
+        Some("recorder-test/1.0")
+    );
+}
@@ -0,0 +1,132 @@
+#![cfg(feature = "mock")]
+//! Exercises the HTTP layer of `WithingsClient` against the `MockServer`.
+
+use serde_json::json;
+use withings_rs::api::{
+    auth, client::StaticToken, measure, notify, user, ApiError, WithingsClient,
+};
+use withings_rs::mock::{MockResponse, MockServer};
+use withings_rs::models::{notify::Appli, MeasureType};
+
+fn client(server: &MockServer) -> WithingsClient {
+    WithingsClient::builder()
+        .token_source(StaticToken("token".to_string()))
+        .base_url(server.base_url())
+        .build()
+        .unwrap()
+}
+
+fn weight_params() -> measure::MeasurementParams {
+    measure::MeasurementParams::builder()
+        .meastype(MeasureType::Weight)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn get_sends_the_token_as_a_bearer_header() {
+    let server = MockServer::start().unwrap();
+    server.respond(
+        "measure",
+        "getmeas",
+        MockResponse::ok(json!({
+            "updatetime": 1706108118,
+            "timezone": "Europe/Paris",
+            "measuregrps": []
+        })),
+    );
+
+    let response = measure::get_measurements(&client(&server), &weight_params()).unwrap();
+    assert_eq!(response.status, 0);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].path, "measure");
+    assert_eq!(requests[0].param("meastype"), Some("1"));
+    assert_eq!(requests[0].header("Authorization"), Some("Bearer token"));
+    assert_eq!(requests[0].param("access_token"), None);
+}
+
+#[test]
+fn get_returns_the_error_status_as_an_api_error() {
+    let server = MockServer::start().unwrap();
+    server.respond(
+        "measure",
+        "getmeas",
+        MockResponse::error(401, "invalid_token: The access token provided is invalid"),
+    );
+
+    let err = measure::get_measurements(&client(&server), &weight_params()).unwrap_err();
+    let api_error = err.downcast_ref::<ApiError>().expect("an ApiError");
+    assert_eq!(api_error.status, 401);
+    assert_eq!(
+        api_error.error.as_deref(),
+        Some("invalid_token: The access token provided is invalid")
+    );
+}
+
+#[test]
+fn get_returns_an_error_for_an_http_error() {
+    let server = MockServer::start().unwrap();
+    server.respond(
+        "v2/user",
+        "getdevice",
+        MockResponse::http(503, "unavailable"),
+    );
+
+    let err = user::get_devices(&client(&server)).unwrap_err();
+    assert!(err.downcast_ref::<ApiError>().is_none());
+    assert!(err.to_string().contains("503"));
+}
+
+#[test]
+fn post_form_refreshes_the_token() {
+    let config = std::env::temp_dir().join(format!("withings-client-{}.json", std::process::id()));
+    std::fs::write(
+        &config,
+        json!({ "access_token": "old", "refresh_token": "refresh" }).to_string(),
+    )
+    .unwrap();
+    std::env::set_var("WITHINGS_CONFIG_FILE", &config);
+
+    let server = MockServer::start().unwrap();
+    server.respond(
+        "v2/oauth2",
+        "requesttoken",
+        MockResponse::token("new", "next"),
+    );
+    server.respond(
+        "v2/oauth2",
+        "requesttoken",
+        MockResponse::error(503, "invalid_params"),
+    );
+    let client = client(&server);
+
+    let token = auth::refresh_token_with(&client, "id".to_string(), "secret".to_string()).unwrap();
+    assert_eq!(token, "new");
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].param("grant_type"), Some("refresh_token"));
+    assert_eq!(requests[0].param("refresh_token"), Some("refresh"));
+    assert_eq!(requests[0].header("Authorization"), None);
+
+    let err =
+        auth::refresh_token_with(&client, "id".to_string(), "secret".to_string()).unwrap_err();
+    assert_eq!(err.downcast_ref::<ApiError>().unwrap().status, 503);
+
+    std::fs::remove_file(&config).unwrap();
+}
+
+#[test]
+fn notify_maps_the_error_status_to_a_typed_error() {
+    let server = MockServer::start().unwrap();
+    server.respond("notify", "get", MockResponse::error(343, "No subscription"));
+    server.respond("notify", "revoke", MockResponse::ok(json!({})));
+    let client = client(&server);
+
+    let err = notify::get_subscription(&client, "https://example.com/hook", Appli::Weight);
+    assert!(matches!(err, Err(notify::NotifyError::NoSuchSubscription)));
+    assert!(notify::revoke(&client, "https://example.com/hook", Appli::Weight).is_ok());
+}
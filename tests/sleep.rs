@@ -0,0 +1,69 @@
+#![cfg(feature = "mock")]
+//! Runs the windowed sleep requests against the `MockServer`.
+
+use serde_json::json;
+use std::time::{Duration, UNIX_EPOCH};
+use withings_rs::api::{client::StaticToken, params::DateRange, sleep, WithingsClient};
+use withings_rs::mock::{MockResponse, MockServer};
+use withings_rs::models::sleep::SleepState;
+
+const START: i64 = 1_704_067_200;
+const DAY: i64 = 24 * 60 * 60;
+
+fn client(server: &MockServer) -> WithingsClient {
+    WithingsClient::builder()
+        .token_source(StaticToken("token".to_string()))
+        .base_url(server.base_url())
+        .build()
+        .unwrap()
+}
+
+fn segment(startdate: i64, enddate: i64, state: SleepState) -> serde_json::Value {
+    json!({ "startdate": startdate, "enddate": enddate, "state": state.id() })
+}
+
+#[test]
+fn timeline_merges_a_segment_across_a_window_boundary() {
+    let server = MockServer::start().unwrap();
+    // The deep segment crosses the end of the first window, so both windows return it
+    let crossing = segment(START + DAY - 600, START + DAY + 600, SleepState::Deep);
+    server.respond(
+        "v2/sleep",
+        "get",
+        MockResponse::ok(json!({ "series": [
+            segment(START + DAY - 3600, START + DAY - 600, SleepState::Light),
+            crossing.clone(),
+        ]})),
+    );
+    server.respond(
+        "v2/sleep",
+        "get",
+        MockResponse::ok(json!({ "series": [
+            crossing,
+            segment(START + DAY + 600, START + DAY + 1800, SleepState::Rem),
+        ]})),
+    );
+
+    let start = UNIX_EPOCH + Duration::from_secs(START as u64);
+    let range = DateRange::new(start, start + Duration::from_secs(DAY as u64 + 3600)).unwrap();
+    let params = sleep::SleepParams::new(range);
+    assert_eq!(params.windows().len(), 2);
+
+    let timeline = sleep::get_sleep_timeline(&client(&server), &params).unwrap();
+    assert_eq!(timeline.states.len(), 3);
+    assert_eq!(
+        timeline.time_in(SleepState::Deep),
+        Duration::from_secs(1200)
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    let first_end = (START + DAY - 1).to_string();
+    let second_start = (START + DAY).to_string();
+    assert_eq!(
+        requests[0].param("startdate"),
+        Some(START.to_string().as_str())
+    );
+    assert_eq!(requests[0].param("enddate"), Some(first_end.as_str()));
+    assert_eq!(requests[1].param("startdate"), Some(second_start.as_str()));
+}
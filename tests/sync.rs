@@ -0,0 +1,143 @@
+#![cfg(feature = "mock")]
+//! Runs the incremental sync against the `MockServer`.
+
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use withings_rs::api::{client::StaticToken, measure::MeasurementParams, WithingsClient};
+use withings_rs::mock::{MockResponse, MockServer};
+use withings_rs::models::MeasureType;
+use withings_rs::sync::{CursorStore, Syncer};
+
+/// Keeps the cursors in memory.
+#[derive(Default)]
+struct MemoryStore(RefCell<HashMap<(String, String), i64>>);
+
+impl CursorStore for MemoryStore {
+    fn load(&self, user: &str, data_type: &str) -> Result<Option<i64>, Box<dyn Error>> {
+        let key = (user.to_string(), data_type.to_string());
+        Ok(self.0.borrow().get(&key).copied())
+    }
+
+    fn save(&self, user: &str, data_type: &str, updatetime: i64) -> Result<(), Box<dyn Error>> {
+        let key = (user.to_string(), data_type.to_string());
+        self.0.borrow_mut().insert(key, updatetime);
+        Ok(())
+    }
+}
+
+fn group(grpid: i64, created: i64) -> Value {
+    json!({
+        "grpid": grpid,
+        "attrib": 0,
+        "date": created,
+        "created": created,
+        "modified": created + 50,
+        "category": 1,
+        "measures": [{ "value": 72480, "type": 1, "unit": -3, "algo": 0, "fm": 0 }],
+        "comment": null
+    })
+}
+
+fn page(updatetime: i64, groups: Vec<Value>, offset: Option<i64>) -> MockResponse {
+    MockResponse::ok(json!({
+        "updatetime": updatetime,
+        "timezone": "Europe/Paris",
+        "measuregrps": groups,
+        "more": offset.is_some(),
+        "offset": offset.unwrap_or(0)
+    }))
+}
+
+fn client(server: &MockServer) -> WithingsClient {
+    WithingsClient::builder()
+        .token_source(StaticToken("token".to_string()))
+        .base_url(server.base_url())
+        .build()
+        .unwrap()
+}
+
+fn weight() -> MeasurementParams {
+    MeasurementParams::builder()
+        .meastype(MeasureType::Weight)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn sync_follows_pages_and_advances_the_cursor() {
+    let server = MockServer::start().unwrap();
+    server.respond(
+        "measure",
+        "getmeas",
+        page(1000, vec![group(1, 100)], Some(1)),
+    );
+    server.respond("measure", "getmeas", page(1010, vec![group(2, 200)], None));
+    server.respond(
+        "measure",
+        "getmeas",
+        page(2000, vec![group(2, 200), group(3, 1500)], None),
+    );
+    let client = client(&server);
+    let syncer = Syncer::new(MemoryStore::default());
+
+    let first = syncer.sync_measures(&client, "me", &weight()).unwrap();
+    assert_eq!(first.since, None);
+    assert_eq!(first.added.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+    assert!(first.modified.is_empty());
+    // The cursor is the updatetime of the first page
+    assert_eq!(first.updatetime, 1000);
+
+    let second = syncer.sync_measures(&client, "me", &weight()).unwrap();
+    assert_eq!(second.since, Some(1000));
+    assert_eq!(second.modified.keys().copied().collect::<Vec<_>>(), vec![2]);
+    assert_eq!(second.added.keys().copied().collect::<Vec<_>>(), vec![3]);
+    assert_eq!(second.updatetime, 2000);
+
+    let requests = server.requests();
+    assert_eq!(requests[0].param("lastupdate"), Some("0"));
+    assert_eq!(requests[1].param("offset"), Some("1"));
+    assert_eq!(requests[2].param("lastupdate"), Some("1000"));
+    assert_eq!(requests[2].param("offset"), None);
+}
+
+#[test]
+fn failed_sync_does_not_advance_the_cursor() {
+    let server = MockServer::start().unwrap();
+    server.respond(
+        "measure",
+        "getmeas",
+        page(1000, vec![group(1, 100)], Some(1)),
+    );
+    server.respond(
+        "measure",
+        "getmeas",
+        MockResponse::error(601, "Too many requests"),
+    );
+    let syncer = Syncer::new(MemoryStore::default());
+
+    assert!(syncer
+        .sync_measures(&client(&server), "me", &weight())
+        .is_err());
+    assert_eq!(
+        syncer.store().load("me", "getmeas:meastype=1").unwrap(),
+        None
+    );
+}
+
+#[test]
+fn sync_rejects_a_date_range() {
+    let server = MockServer::start().unwrap();
+    let params = MeasurementParams::builder()
+        .meastype(MeasureType::Weight)
+        .startdate(std::time::UNIX_EPOCH)
+        .build()
+        .unwrap();
+    let syncer = Syncer::new(MemoryStore::default());
+
+    assert!(syncer
+        .sync_measures(&client(&server), "me", &params)
+        .is_err());
+    assert!(server.requests().is_empty());
+}